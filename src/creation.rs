@@ -0,0 +1,11 @@
+//! Cold observables: each subscription runs the source from the start.
+mod from_iter;
+pub use from_iter::{from_iter, of, range, FromIterOp, RangeIter};
+mod empty;
+pub use empty::{empty, never, throw, EmptyOp, NeverOp, ThrowOp};
+mod create;
+pub use create::{create, CreateOp, CreateSubscription, Subscriber};
+mod defer;
+pub use defer::{defer, DeferOp};
+mod interval;
+pub use interval::{interval, timer, IntervalOp, TimerOp};
@@ -0,0 +1,373 @@
+use crate::{ErrComplete, OState, Observable, Observer, Subscription};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+type NextCallback<'a, T> = Box<dyn FnMut(T) -> OState + 'a>;
+type ErrCompleteCallback<'a, E> = Box<dyn FnMut(&ErrComplete<E>) + 'a>;
+type Teardown<'a> = Box<dyn FnOnce() + 'a>;
+
+/// Creates a cold observable from a producer function, called once per
+/// subscription.
+///
+/// The producer receives a `Subscriber` to emit to, which it may keep to
+/// emit later, e.g. from the callback of an event source. It returns a
+/// teardown closure, run once the subscription ends, either by
+/// unsubscribing or by the producer completing or failing.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::create, ErrComplete, Observable, Observer, Subscription,
+/// # };
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// // stands for an event source taking callbacks, like a C library.
+/// let listeners = Rc::new(RefCell::new(vec![]));
+///
+/// let for_producer = listeners.clone();
+/// let events = create(move |subscriber| {
+///   for_producer.borrow_mut().push(subscriber);
+///   let listeners = for_producer.clone();
+///   move || listeners.borrow_mut().clear()
+/// });
+///
+/// let subscription =
+///   events.subscribe(|v| println!("{}", v), |_: &ErrComplete<()>| {});
+/// listeners.borrow().iter().for_each(|s| {
+///   s.next(1);
+/// });
+///
+/// subscription.unsubscribe();
+/// assert!(listeners.borrow().is_empty());
+/// ```
+pub fn create<'a, F, T, E, TD>(producer: F) -> CreateOp<F, T, E>
+where
+  F: FnOnce(Subscriber<'a, T, E>) -> TD,
+  TD: FnOnce() + 'a,
+{
+  CreateOp {
+    producer,
+    _item: PhantomData,
+  }
+}
+
+pub struct CreateOp<F, T, E> {
+  producer: F,
+  _item: PhantomData<fn() -> (T, E)>,
+}
+
+impl<F: Clone, T, E> Clone for CreateOp<F, T, E> {
+  fn clone(&self) -> Self {
+    CreateOp {
+      producer: self.producer.clone(),
+      _item: PhantomData,
+    }
+  }
+}
+
+impl<'a, F, T, E, TD> Observable<'a> for CreateOp<F, T, E>
+where
+  F: FnOnce(Subscriber<'a, T, E>) -> TD,
+  TD: FnOnce() + 'a,
+  T: 'a,
+  E: 'a,
+{
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = CreateSubscription<'a, T, E>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let state = Rc::new(SubscriberState {
+      closed: Cell::new(false),
+      next: RefCell::new(Some(Box::new(next))),
+      err_or_complete: RefCell::new(Some(Box::new(err_or_complete))),
+      teardown: RefCell::new(None),
+      emitting: Cell::new(false),
+      queue: RefCell::new(VecDeque::new()),
+      terminal: RefCell::new(None),
+    });
+
+    let teardown = (self.producer)(Subscriber {
+      state: state.clone(),
+    });
+    if state.closed.get() {
+      teardown();
+    } else {
+      *state.teardown.borrow_mut() = Some(Box::new(teardown));
+    }
+
+    CreateSubscription { state }
+  }
+}
+
+struct SubscriberState<'a, T, E> {
+  closed: Cell<bool>,
+  next: RefCell<Option<NextCallback<'a, T>>>,
+  err_or_complete: RefCell<Option<ErrCompleteCallback<'a, E>>>,
+  teardown: RefCell<Option<Teardown<'a>>>,
+  /// The observer is running, what is emitted meanwhile is queued.
+  emitting: Cell<bool>,
+  queue: RefCell<VecDeque<T>>,
+  /// A terminal sent while emitting, once the queued items are.
+  terminal: RefCell<Option<ErrComplete<E>>>,
+}
+
+impl<'a, T, E> SubscriberState<'a, T, E> {
+  /// Release the observer and run the teardown.
+  fn close(&self) {
+    self.closed.set(true);
+    // the observer may be closing us from inside its own `next`, then the
+    // callback is released once it returns.
+    if let Ok(mut next) = self.next.try_borrow_mut() {
+      next.take();
+    }
+    self.queue.borrow_mut().clear();
+    self.terminal.borrow_mut().take();
+    if let Ok(mut ec) = self.err_or_complete.try_borrow_mut() {
+      ec.take();
+    }
+    let teardown = self.teardown.borrow_mut().take();
+    if let Some(teardown) = teardown {
+      teardown();
+    }
+  }
+
+  fn err_or_complete(&self, ec: ErrComplete<E>) {
+    if self.closed.get() {
+      return;
+    }
+    self.closed.set(true);
+    if self.emitting.get() {
+      *self.terminal.borrow_mut() = Some(ec);
+    } else {
+      self.send_terminal(&ec);
+    }
+  }
+
+  fn send_terminal(&self, ec: &ErrComplete<E>) {
+    let callback = self.err_or_complete.borrow_mut().take();
+    if let Some(mut callback) = callback {
+      callback(ec);
+    }
+    self.close();
+  }
+}
+
+/// The handle a `create` producer emits to.
+///
+/// Clones emit to the same observer. Once the subscriber is closed, by
+/// unsubscribing or by a completion or an error, everything it emits is
+/// ignored.
+pub struct Subscriber<'a, T, E> {
+  state: Rc<SubscriberState<'a, T, E>>,
+}
+
+impl<'a, T, E> Clone for Subscriber<'a, T, E> {
+  fn clone(&self) -> Self {
+    Subscriber {
+      state: self.state.clone(),
+    }
+  }
+}
+
+impl<'a, T, E> Subscriber<'a, T, E> {
+  /// Return true if the observer doesn't accept anything anymore, a
+  /// producer should stop producing then.
+  pub fn is_closed(&self) -> bool { self.state.closed.get() }
+}
+
+impl<'a, T, E> Observer for Subscriber<'a, T, E> {
+  type Item = T;
+  type Err = E;
+
+  fn next(&self, v: Self::Item) -> &Self {
+    let state = &self.state;
+    if state.closed.get() {
+      return self;
+    }
+    if state.emitting.get() {
+      // emitted by the observer itself, it gets it once it returns.
+      state.queue.borrow_mut().push_back(v);
+      return self;
+    }
+
+    state.emitting.set(true);
+    let mut v = v;
+    let observer_state = loop {
+      let observer_state = match state.next.borrow_mut().as_mut() {
+        Some(next) => next(v),
+        None => OState::Complete,
+      };
+      // unsubscribed meanwhile, not just waiting to send a terminal.
+      let unsubscribed =
+        state.closed.get() && state.terminal.borrow().is_none();
+      if observer_state == OState::Complete || unsubscribed {
+        break observer_state;
+      }
+      let queued = state.queue.borrow_mut().pop_front();
+      match queued {
+        Some(queued) => v = queued,
+        None => break observer_state,
+      }
+    };
+    state.emitting.set(false);
+
+    let terminal = state.terminal.borrow_mut().take();
+    if observer_state == OState::Complete {
+      state.close();
+    } else if let Some(ec) = terminal {
+      state.send_terminal(&ec);
+    } else if state.closed.get() {
+      // unsubscribed while emitting, the callback can be released now.
+      state.close();
+    }
+    self
+  }
+
+  fn complete(self) { self.state.err_or_complete(ErrComplete::Complete); }
+
+  fn err(self, err: Self::Err) {
+    self.state.err_or_complete(ErrComplete::Err(err));
+  }
+}
+
+pub struct CreateSubscription<'a, T, E> {
+  state: Rc<SubscriberState<'a, T, E>>,
+}
+
+impl<'a, T, E> Subscription for CreateSubscription<'a, T, E> {
+  fn unsubscribe(self) { self.state.close(); }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::create, ops::Map, ErrComplete, Observable, Observer, Subscription,
+  };
+  use std::cell::{Cell, RefCell};
+  use std::rc::Rc;
+
+  #[test]
+  fn sync_producer() {
+    let torn_down = Rc::new(Cell::new(0));
+    let t = torn_down.clone();
+    let source = create(move |subscriber| {
+      for i in 0..3 {
+        subscriber.next(i);
+      }
+      subscriber.clone().complete();
+      subscriber.next(100);
+      move || t.set(t.get() + 1)
+    });
+
+    let notifications = RefCell::new(vec![]);
+    source.clone().map(|v| v * 2).subscribe(
+      |v| notifications.borrow_mut().push(format!("{}", v)),
+      |ec: &ErrComplete<()>| {
+        notifications.borrow_mut().push(format!("{:?}", ec))
+      },
+    );
+    assert_eq!(*notifications.borrow(), vec!["0", "2", "4", "Complete"]);
+    assert_eq!(torn_down.get(), 1);
+
+    // cold, producing again for another subscription.
+    let mut count = 0;
+    source.subscribe_next(|_| count += 1);
+    assert_eq!(count, 3);
+    assert_eq!(torn_down.get(), 2);
+  }
+
+  #[test]
+  fn async_producer_unsubscribe() {
+    let subscribers = Rc::new(RefCell::new(vec![]));
+    let torn_down = Rc::new(Cell::new(false));
+
+    let s = subscribers.clone();
+    let t = torn_down.clone();
+    let received = Rc::new(RefCell::new(vec![]));
+    let r = received.clone();
+    let subscription = create(move |subscriber| {
+      s.borrow_mut().push(subscriber);
+      move || t.set(true)
+    })
+    .subscribe(move |v| r.borrow_mut().push(v), |_: &ErrComplete<()>| {});
+
+    let subscriber = subscribers.borrow()[0].clone();
+    subscriber.next(1).next(2);
+    assert!(!subscriber.is_closed());
+    assert!(!torn_down.get());
+
+    subscription.unsubscribe();
+    assert!(subscriber.is_closed());
+    assert!(torn_down.get());
+    subscriber.next(3);
+    subscriber.complete();
+    assert_eq!(*received.borrow(), vec![1, 2]);
+  }
+
+  #[test]
+  fn error_runs_teardown_once() {
+    let torn_down = Rc::new(Cell::new(0));
+    let errors = Rc::new(RefCell::new(vec![]));
+    let subscriber = Rc::new(RefCell::new(None));
+
+    let s = subscriber.clone();
+    let t = torn_down.clone();
+    let e = errors.clone();
+    let subscription = create(move |subscriber| {
+      *s.borrow_mut() = Some(subscriber);
+      move || t.set(t.get() + 1)
+    })
+    .subscribe(
+      |_: i32| {},
+      move |ec: &ErrComplete<&str>| e.borrow_mut().push(ec.clone()),
+    );
+
+    let subscriber = subscriber.borrow_mut().take().unwrap();
+    subscriber.clone().err("boom");
+    subscriber.err("again");
+    subscription.unsubscribe();
+    assert_eq!(*errors.borrow(), vec![ErrComplete::Err("boom")]);
+    assert_eq!(torn_down.get(), 1);
+  }
+
+  #[test]
+  fn observer_emits_to_its_subscriber() {
+    let subscriber = Rc::new(RefCell::new(None));
+    let s = subscriber.clone();
+    let source = create(move |subscriber| {
+      *s.borrow_mut() = Some(subscriber);
+      || {}
+    });
+
+    let notifications = Rc::new(RefCell::new(vec![]));
+    let n = notifications.clone();
+    let for_observer = subscriber.clone();
+    let n_ec = notifications.clone();
+    source.subscribe(
+      move |v| {
+        n.borrow_mut().push(format!("{}", v));
+        if v == 0 {
+          let subscriber = for_observer.borrow().clone().unwrap();
+          subscriber.next(1).next(2);
+          subscriber.complete();
+        }
+      },
+      move |ec: &ErrComplete<()>| n_ec.borrow_mut().push(format!("{:?}", ec)),
+    );
+
+    let producer = subscriber.borrow().clone().unwrap();
+    producer.next(0);
+    assert_eq!(*notifications.borrow(), vec!["0", "1", "2", "Complete"]);
+    assert!(producer.is_closed());
+  }
+}
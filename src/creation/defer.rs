@@ -0,0 +1,102 @@
+use crate::{ErrComplete, OState, Observable};
+
+/// Creates an observable that builds a new inner observable for each
+/// subscription, by calling `factory` at subscribe time.
+///
+/// As subscribing consumes an observable, subscribe clones of the deferred
+/// observable to subscribe several times, each one runs the factory again.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::{defer, of}, Observable};
+/// # use std::cell::Cell;
+/// let config = Cell::new("debug");
+/// let current_config = defer(|| of::<_, ()>(config.get()));
+///
+/// config.set("release");
+/// let mut received = None;
+/// current_config.subscribe_next(|v| received = Some(v));
+/// // the config is read when subscribing, not when deferring.
+/// assert_eq!(received, Some("release"));
+/// ```
+pub fn defer<F, O>(factory: F) -> DeferOp<F>
+where
+  F: FnOnce() -> O,
+{
+  DeferOp { factory }
+}
+
+#[derive(Clone)]
+pub struct DeferOp<F> {
+  factory: F,
+}
+
+impl<'a, F, O> Observable<'a> for DeferOp<F>
+where
+  F: FnOnce() -> O,
+  O: Observable<'a>,
+{
+  type Item = O::Item;
+  type Err = O::Err;
+  type Unsubscribe = O::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    (self.factory)().subscribe_return_state(next, err_or_complete)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{defer, from_iter},
+    ops::Merge,
+    ErrComplete, Observable, Observer, Subject, Subscription,
+  };
+  use std::cell::{Cell, RefCell};
+  use std::rc::Rc;
+
+  #[test]
+  fn factory_called_per_subscription() {
+    let connections = Rc::new(Cell::new(0));
+    let c = connections.clone();
+    let deferred = defer(move || {
+      c.set(c.get() + 1);
+      from_iter::<_, ()>(0..c.get())
+    });
+    assert_eq!(connections.get(), 0);
+
+    let mut first = vec![];
+    deferred.clone().subscribe_next(|v| first.push(v));
+    let mut second = vec![];
+    deferred.subscribe_next(|v| second.push(v));
+
+    assert_eq!(connections.get(), 2);
+    assert_eq!(first, vec![0]);
+    assert_eq!(second, vec![0, 1]);
+  }
+
+  #[test]
+  fn inner_subscription_returned() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let a = Subject::<i32, ()>::new();
+    let b = Subject::new();
+
+    let (ca, cb) = (a.clone(), b.clone());
+    let r = received.clone();
+    let subscription = defer(move || ca.merge(cb))
+      .subscribe(move |v| r.borrow_mut().push(v), |_: &ErrComplete<()>| {});
+    a.next(1);
+    b.next(2);
+    subscription.unsubscribe();
+    a.next(3);
+    b.next(4);
+    assert_eq!(*received.borrow(), vec![1, 2]);
+  }
+}
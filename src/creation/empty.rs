@@ -0,0 +1,111 @@
+use crate::{ErrComplete, OState, Observable};
+use std::marker::PhantomData;
+
+/// Creates an observable completing immediately, without any item.
+pub fn empty<T, E>() -> EmptyOp<T, E> { EmptyOp(PhantomData) }
+
+/// Creates an observable that never emits anything, not even completion.
+pub fn never<T, E>() -> NeverOp<T, E> { NeverOp(PhantomData) }
+
+/// Creates an observable failing immediately with `err`, without any item.
+pub fn throw<T, E>(err: E) -> ThrowOp<T, E> {
+  ThrowOp {
+    err,
+    _item: PhantomData,
+  }
+}
+
+pub struct EmptyOp<T, E>(PhantomData<fn() -> (T, E)>);
+
+impl<T, E> Clone for EmptyOp<T, E> {
+  fn clone(&self) -> Self { EmptyOp(PhantomData) }
+}
+
+impl<'a, T, E> Observable<'a> for EmptyOp<T, E> {
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = ();
+
+  fn subscribe_return_state<N, EC>(self, _next: N, mut err_or_complete: EC)
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    err_or_complete(&ErrComplete::Complete);
+  }
+}
+
+pub struct NeverOp<T, E>(PhantomData<fn() -> (T, E)>);
+
+impl<T, E> Clone for NeverOp<T, E> {
+  fn clone(&self) -> Self { NeverOp(PhantomData) }
+}
+
+impl<'a, T, E> Observable<'a> for NeverOp<T, E> {
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = ();
+
+  fn subscribe_return_state<N, EC>(self, _next: N, _err_or_complete: EC)
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+  }
+}
+
+#[derive(Clone)]
+pub struct ThrowOp<T, E> {
+  err: E,
+  _item: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, E> Observable<'a> for ThrowOp<T, E> {
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = ();
+
+  fn subscribe_return_state<N, EC>(self, _next: N, mut err_or_complete: EC)
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    err_or_complete(&ErrComplete::Err(self.err));
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{empty, never, of, throw},
+    ops::Merge,
+    ErrComplete, Observable,
+  };
+
+  #[test]
+  fn empty_completes() {
+    let mut notifications = vec![];
+    empty::<i32, ()>()
+      .subscribe(|_| unreachable!(), |ec| notifications.push(ec.clone()));
+    assert_eq!(notifications, vec![ErrComplete::Complete]);
+  }
+
+  #[test]
+  fn never_emits_nothing() {
+    never::<i32, ()>().subscribe(|_| unreachable!(), |_| unreachable!());
+    // merging with never never completes.
+    let mut received = vec![];
+    of(1)
+      .merge(never())
+      .subscribe(|v| received.push(v), |_: &ErrComplete<()>| unreachable!());
+    assert_eq!(received, vec![1]);
+  }
+
+  #[test]
+  fn throw_fails() {
+    let mut notifications = vec![];
+    throw::<i32, _>("boom")
+      .subscribe(|_| unreachable!(), |ec| notifications.push(ec.clone()));
+    assert_eq!(notifications, vec![ErrComplete::Err("boom")]);
+  }
+}
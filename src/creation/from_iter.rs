@@ -0,0 +1,179 @@
+use crate::{ErrComplete, OState, Observable};
+use std::iter::{self, Once};
+use std::marker::PhantomData;
+use std::ops::RangeFrom;
+
+/// Creates an observable emitting all the items of an iterator, then
+/// completing. The items are emitted synchronously, by `subscribe`.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::Map, Observable};
+/// let mut squares = vec![];
+/// from_iter::<_, ()>(vec![1, 2, 3])
+///   .map(|v| v * v)
+///   .subscribe_next(|v| squares.push(v));
+/// assert_eq!(squares, vec![1, 4, 9]);
+/// ```
+pub fn from_iter<I, E>(iter: I) -> FromIterOp<I::IntoIter, E>
+where
+  I: IntoIterator,
+{
+  FromIterOp {
+    iter: iter.into_iter(),
+    _err: PhantomData,
+  }
+}
+
+/// Creates an observable emitting a single value, then completing.
+pub fn of<T, E>(v: T) -> FromIterOp<Once<T>, E> { from_iter(iter::once(v)) }
+
+/// Creates an observable emitting `count` sequential numbers from `start`,
+/// then completing.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::range, Observable};
+/// let mut numbers = vec![];
+/// range::<_, ()>(3, 4).subscribe_next(|v| numbers.push(v));
+/// assert_eq!(numbers, vec![3, 4, 5, 6]);
+/// ```
+pub fn range<T, E>(start: T, count: usize) -> FromIterOp<RangeIter<T>, E>
+where
+  T: Clone,
+  RangeFrom<T>: Iterator<Item = T>,
+{
+  from_iter(RangeIter {
+    from: start..,
+    remaining: count,
+  })
+}
+
+/// The numbers of [`range`](fn.range.html). Unlike `(start..).take(count)`,
+/// it never computes the number after the last one, so a range may end at
+/// the maximum value of its type.
+#[derive(Clone)]
+pub struct RangeIter<T> {
+  from: RangeFrom<T>,
+  remaining: usize,
+}
+
+impl<T> Iterator for RangeIter<T>
+where
+  T: Clone,
+  RangeFrom<T>: Iterator<Item = T>,
+{
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    match self.remaining {
+      0 => None,
+      1 => {
+        self.remaining = 0;
+        Some(self.from.start.clone())
+      }
+      _ => {
+        self.remaining -= 1;
+        self.from.next()
+      }
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+pub struct FromIterOp<I, E> {
+  iter: I,
+  _err: PhantomData<fn() -> E>,
+}
+
+impl<I: Clone, E> Clone for FromIterOp<I, E> {
+  fn clone(&self) -> Self {
+    FromIterOp {
+      iter: self.iter.clone(),
+      _err: PhantomData,
+    }
+  }
+}
+
+impl<'a, I, E> Observable<'a> for FromIterOp<I, E>
+where
+  I: Iterator,
+{
+  type Item = I::Item;
+  type Err = E;
+  type Unsubscribe = ();
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, mut err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    for v in self.iter {
+      if next(v) == OState::Complete {
+        return;
+      }
+    }
+    err_or_complete(&ErrComplete::Complete);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, of, range},
+    ops::{Filter, Merge},
+    ErrComplete, Observable,
+  };
+  use std::cell::RefCell;
+
+  #[test]
+  fn from_iter_emits_then_completes() {
+    let notifications = RefCell::new(vec![]);
+    from_iter(0..3).subscribe(
+      |v| notifications.borrow_mut().push(format!("{}", v)),
+      |ec: &ErrComplete<()>| {
+        notifications.borrow_mut().push(format!("{:?}", ec))
+      },
+    );
+    assert_eq!(notifications.into_inner(), vec!["0", "1", "2", "Complete"]);
+  }
+
+  #[test]
+  fn subscribe_clones_again() {
+    let numbers = from_iter::<_, ()>(vec![1, 2, 3]);
+    let mut sum = 0;
+    numbers.clone().subscribe_next(|v| sum += v);
+    numbers.filter(|v| *v > 1).subscribe_next(|v| sum += v);
+    assert_eq!(sum, 11);
+  }
+
+  #[test]
+  fn of_and_range() {
+    let mut received = vec![];
+    let mut completed = 0;
+    of(100)
+      .merge(range(1, 3))
+      .subscribe(|v| received.push(v), |_: &ErrComplete<()>| completed += 1);
+    assert_eq!(received, vec![100, 1, 2, 3]);
+    assert_eq!(completed, 1);
+  }
+
+  #[test]
+  fn range_ending_at_max() {
+    let mut received = vec![];
+    range::<u8, ()>(254, 2).subscribe_next(|v| received.push(v));
+    assert_eq!(received, vec![254, 255]);
+
+    let mut completed = false;
+    range(i32::MAX, 0)
+      .subscribe(|_| unreachable!(), |_: &ErrComplete<()>| completed = true);
+    assert!(completed);
+  }
+}
@@ -0,0 +1,282 @@
+use crate::{
+  scheduler::{LocalScheduler, Scheduler, SchedulerSubscription},
+  ErrComplete, OState, Observable, Subscription,
+};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// Creates an observable emitting sequential numbers every `period` on
+/// `scheduler`, it never completes.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::interval, scheduler::{LocalScheduler, TrampolineScheduler},
+/// #   Observable, Subscription,
+/// # };
+/// # use std::cell::RefCell;
+/// # use std::time::Duration;
+/// let ticks = RefCell::new(vec![]);
+/// {
+///   let scheduler = TrampolineScheduler::new();
+///   let inner = scheduler.clone();
+///   let ticks = &ticks;
+///   scheduler.schedule_local(
+///     move |_| {
+///       let ms = Duration::from_millis;
+///       let ticking = interval::<_, ()>(ms(10), inner.clone())
+///         .subscribe_next(move |v| ticks.borrow_mut().push(v));
+///       // the trampoline runs until no task is left, so stop ticking.
+///       inner.schedule_local(move |_| ticking.unsubscribe(), Some(ms(35)));
+///     },
+///     None,
+///   );
+/// }
+/// assert_eq!(ticks.into_inner(), vec![0, 1, 2]);
+/// ```
+///
+/// On a [`Scheduler`](../scheduler/trait.Scheduler.html) that may run tasks
+/// on another thread, subscribe by `subscribe_send`:
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::interval, scheduler::NewThreadScheduler, Subscription,
+/// # };
+/// # use std::sync::mpsc::channel;
+/// # use std::time::Duration;
+/// let (tx, rx) = channel();
+/// let period = Duration::from_millis(1);
+/// let ticking = interval::<_, ()>(period, NewThreadScheduler)
+///   .subscribe_send(move |v| tx.send(v).unwrap(), |_| {});
+/// assert_eq!(rx.iter().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+/// ticking.unsubscribe();
+/// ```
+pub fn interval<S, E>(period: Duration, scheduler: S) -> IntervalOp<S, E> {
+  IntervalOp {
+    period,
+    scheduler,
+    _err: PhantomData,
+  }
+}
+
+/// Creates an observable emitting `0` after `delay` on `scheduler`, then
+/// completing.
+pub fn timer<S, E>(delay: Duration, scheduler: S) -> TimerOp<S, E> {
+  TimerOp {
+    delay,
+    scheduler,
+    _err: PhantomData,
+  }
+}
+
+pub struct IntervalOp<S, E> {
+  period: Duration,
+  scheduler: S,
+  _err: PhantomData<fn() -> E>,
+}
+
+impl<S: Clone, E> Clone for IntervalOp<S, E> {
+  fn clone(&self) -> Self { interval(self.period, self.scheduler.clone()) }
+}
+
+impl<'a, S, E> Observable<'a> for IntervalOp<S, E>
+where
+  S: LocalScheduler<'a>,
+{
+  type Item = usize;
+  type Err = E;
+  type Unsubscribe = SchedulerSubscription;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, _err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut count = 0;
+    self.scheduler.schedule_periodic_local(
+      move |subscription| {
+        if next(count) == OState::Complete {
+          subscription.unsubscribe();
+        }
+        count += 1;
+      },
+      self.period,
+    )
+  }
+}
+
+impl<S: Scheduler, E> IntervalOp<S, E> {
+  /// Subscribe observers able to move to another thread, as `scheduler`
+  /// may run the ticks there. The other ways to subscribe need a
+  /// `LocalScheduler`.
+  pub fn subscribe_send<N, EC>(
+    self, mut next: N, _err_or_complete: EC,
+  ) -> SchedulerSubscription
+  where
+    N: FnMut(usize) + Send + 'static,
+    EC: FnMut(&ErrComplete<E>) + Send + 'static,
+  {
+    let mut count = 0;
+    self.scheduler.schedule_periodic(
+      move |_| {
+        next(count);
+        count += 1;
+      },
+      self.period,
+    )
+  }
+}
+
+pub struct TimerOp<S, E> {
+  delay: Duration,
+  scheduler: S,
+  _err: PhantomData<fn() -> E>,
+}
+
+impl<S: Clone, E> Clone for TimerOp<S, E> {
+  fn clone(&self) -> Self { timer(self.delay, self.scheduler.clone()) }
+}
+
+impl<'a, S, E> Observable<'a> for TimerOp<S, E>
+where
+  S: LocalScheduler<'a>,
+{
+  type Item = usize;
+  type Err = E;
+  type Unsubscribe = SchedulerSubscription;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, mut err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    self.scheduler.schedule_local(
+      move |_| {
+        if next(0) == OState::Next {
+          err_or_complete(&ErrComplete::Complete);
+        }
+      },
+      Some(self.delay),
+    )
+  }
+}
+
+impl<S: Scheduler, E> TimerOp<S, E> {
+  /// Subscribe observers able to move to another thread, as `scheduler`
+  /// may run the timer there. The other ways to subscribe need a
+  /// `LocalScheduler`.
+  pub fn subscribe_send<N, EC>(
+    self, mut next: N, mut err_or_complete: EC,
+  ) -> SchedulerSubscription
+  where
+    N: FnMut(usize) + Send + 'static,
+    EC: FnMut(&ErrComplete<E>) + Send + 'static,
+  {
+    self.scheduler.schedule(
+      move |_| {
+        next(0);
+        err_or_complete(&ErrComplete::Complete);
+      },
+      Some(self.delay),
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{interval, timer},
+    ops::{Map, Merge, Take},
+    scheduler::{
+      LocalScheduler, NewThreadScheduler, TestScheduler, TrampolineScheduler,
+    },
+    testing::{TestObserver, FRAME},
+    ErrComplete, Observable, Subscription,
+  };
+  use std::cell::RefCell;
+  use std::sync::mpsc::channel;
+  use std::time::{Duration, Instant};
+
+  #[test]
+  fn timer_emits_once_then_completes() {
+    let start = Instant::now();
+    let notifications = RefCell::new(vec![]);
+    timer(Duration::from_millis(5), TrampolineScheduler::new()).subscribe(
+      |v| notifications.borrow_mut().push(format!("{}", v)),
+      |ec: &ErrComplete<()>| {
+        notifications.borrow_mut().push(format!("{:?}", ec))
+      },
+    );
+    assert!(start.elapsed() >= Duration::from_millis(5));
+    assert_eq!(notifications.into_inner(), vec!["0", "Complete"]);
+  }
+
+  #[test]
+  fn interval_until_unsubscribed() {
+    let ticks = RefCell::new(vec![]);
+    {
+      let scheduler = TrampolineScheduler::new();
+      let inner = scheduler.clone();
+      let ticks = &ticks;
+      scheduler.schedule_local(
+        move |_| {
+          let ms = Duration::from_millis;
+          let fast = interval(ms(10), inner.clone()).map(|v| v * 10);
+          let slow = interval(ms(22), inner.clone());
+          let subscription = fast.merge(slow).subscribe(
+            move |v| ticks.borrow_mut().push(v),
+            |_: &ErrComplete<()>| {},
+          );
+          inner
+            .schedule_local(move |_| subscription.unsubscribe(), Some(ms(55)));
+        },
+        None,
+      );
+    }
+    assert_eq!(ticks.into_inner(), vec![0, 10, 0, 20, 30, 1, 40]);
+  }
+
+  #[test]
+  fn take_cancels_interval() {
+    let scheduler = TestScheduler::new();
+    let observer = TestObserver::<_, ()>::with_scheduler(&scheduler);
+    observer.subscribe_to(interval(FRAME, scheduler.clone()).take(3));
+    // an endless interval would make flush panic.
+    scheduler.flush();
+    observer.assert_values(&[0, 1, 2]);
+    observer.assert_completed();
+    assert_eq!(scheduler.now(), FRAME * 3);
+  }
+
+  #[test]
+  fn on_new_thread() {
+    let (tx, rx) = channel();
+    let timer_tx = tx.clone();
+    timer::<_, ()>(Duration::from_millis(1), NewThreadScheduler)
+      .subscribe_send(
+        move |v| {
+          // the receiver may be dropped already, once the test has returned.
+          let _ = timer_tx.send(format!("timer {}", v));
+        },
+        |ec| assert_eq!(ec, &ErrComplete::Complete),
+      );
+    assert_eq!(rx.recv().unwrap(), "timer 0");
+
+    let ticking =
+      interval::<_, ()>(Duration::from_millis(1), NewThreadScheduler)
+        .subscribe_send(
+          move |v| {
+            let _ = tx.send(format!("tick {}", v));
+          },
+          |_| {},
+        );
+    let ticks = rx.iter().take(2).collect::<Vec<_>>();
+    assert_eq!(ticks, vec!["tick 0", "tick 1"]);
+    ticking.unsubscribe();
+  }
+}
@@ -1,10 +1,19 @@
 #![feature(external_doc)]
 #![doc(include = "../README.md")]
 
+pub mod creation;
 pub mod ops;
+pub mod scheduler;
 pub mod subject;
+pub mod subscription;
+pub mod testing;
 
 pub use subject::Subject;
+pub use subscription::SubscriptionGuard;
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
 
 pub trait Observable<'a>: Sized {
   /// The type of the elements being emitted.
@@ -14,19 +23,159 @@ pub trait Observable<'a>: Sized {
   // the Subscription subsribe method return.
   type Unsubscribe: Subscription;
 
-  fn subscribe<N, EC>(self, next: N, err_or_complete: EC) -> Self::Unsubscribe
+  /// Subscribe with a `next` returning whether it wants more items. Once it
+  /// returns `OState::Complete` the observable stops emitting to it, neither
+  /// completion nor error follows, and releases it.
+  ///
+  /// Every observable implements this, so operators like `take` can stop
+  /// their source from inside `next`, even a source emitting synchronously
+  /// from `subscribe`.
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
   where
-    N: 'a + FnMut(Self::Item),
+    N: 'a + FnMut(Self::Item) -> OState,
     EC: 'a + FnMut(&ErrComplete<Self::Err>);
 
+  fn subscribe<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item),
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    self.subscribe_return_state(
+      move |v| {
+        next(v);
+        OState::Next
+      },
+      err_or_complete,
+    )
+  }
+
+  /// Subscribe to the items only.
+  ///
+  /// # Panics
+  ///
+  /// An error is not expected, the error callback panics with it.
+  fn subscribe_next<N>(self, next: N) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item),
+    Self::Err: Debug,
+  {
+    self.subscribe(next, |ec| {
+      if let ErrComplete::Err(err) = ec {
+        panic!("unhandled error from an observable: {:?}", err)
+      }
+    })
+  }
+
+  /// Subscribe to the items and the error, completion is ignored.
+  fn subscribe_err<N, ER>(self, next: N, mut err: ER) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item),
+    ER: 'a + FnMut(&Self::Err),
+  {
+    self.subscribe(next, move |ec| {
+      if let ErrComplete::Err(e) = ec {
+        err(e)
+      }
+    })
+  }
+
+  /// Subscribe with a callback for each kind of notification.
+  fn subscribe_all<N, ER, C>(
+    self, next: N, mut err: ER, mut complete: C,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item),
+    ER: 'a + FnMut(&Self::Err),
+    C: 'a + FnMut(),
+  {
+    self.subscribe(next, move |ec| match ec {
+      ErrComplete::Complete => complete(),
+      ErrComplete::Err(e) => err(e),
+    })
+  }
+
+  /// Subscribe an `Observer`, it receives every item by `next`, and then
+  /// either `complete` or `err`.
+  ///
+  /// A `Subject` is an observer too, so one subject can be piped into
+  /// another. The error is cloned, since `Observer::err` takes it by value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// # use rx_rs::{Observable, Observer, Subject};
+  /// # use std::cell::Cell;
+  /// struct Average {
+  ///   sum: Cell<f32>,
+  ///   count: Cell<usize>,
+  /// }
+  ///
+  /// impl Observer for Average {
+  ///   type Item = f32;
+  ///   type Err = ();
+  ///
+  ///   fn next(&self, v: f32) -> &Self {
+  ///     self.sum.set(self.sum.get() + v);
+  ///     self.count.set(self.count.get() + 1);
+  ///     self
+  ///   }
+  ///
+  ///   fn complete(self) {
+  ///     println!("average: {}", self.sum.get() / self.count.get() as f32);
+  ///   }
+  ///
+  ///   fn err(self, _: ()) { println!("no average") }
+  /// }
+  ///
+  /// let readings = Subject::new();
+  /// readings.clone().subscribe_observer(Average {
+  ///   sum: Cell::new(0.),
+  ///   count: Cell::new(0),
+  /// });
+  /// readings.next(1.).next(2.);
+  /// readings.complete();
+  /// ```
+  fn subscribe_observer<O>(self, observer: O) -> Self::Unsubscribe
+  where
+    O: Observer<Item = Self::Item, Err = Self::Err> + 'a,
+    Self::Err: Clone,
+  {
+    // `complete` and `err` consume the observer, so take it out of the cell
+    // on the terminal notification.
+    let observer = Rc::new(RefCell::new(Some(observer)));
+    let for_ec = observer.clone();
+    self.subscribe(
+      move |v| {
+        if let Some(o) = observer.borrow().as_ref() {
+          o.next(v);
+        }
+      },
+      move |ec| {
+        if let Some(o) = for_ec.borrow_mut().take() {
+          match ec {
+            ErrComplete::Complete => o.complete(),
+            ErrComplete::Err(err) => o.err(err.clone()),
+          }
+        }
+      },
+    )
+  }
+
   fn broadcast(self) -> Subject<'a, Self::Item, Self::Err>
   where
     Self: 'a,
+    Self::Item: Clone,
+    Self::Err: Clone,
   {
     Subject::from_stream(self)
   }
 }
 
+/// A consumer of the notifications an `Observable` emits.
 pub trait Observer {
   type Item;
   type Err;
@@ -40,9 +189,159 @@ pub trait Observer {
 
 pub trait Subscription {
   fn unsubscribe(self);
+
+  /// Wrap the subscription in a guard that unsubscribes it when dropped, so
+  /// it can be tied to the lifetime of its owner.
+  fn unsubscribe_when_dropped(self) -> SubscriptionGuard<Self>
+  where
+    Self: Sized,
+  {
+    SubscriptionGuard::new(self)
+  }
 }
 
+/// What an observer wants after receiving an item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OState {
+  /// Keep emitting to it.
+  Next,
+  /// It's done, stop emitting to it.
+  Complete,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum ErrComplete<E> {
   Complete,
   Err(E),
 }
+
+#[cfg(test)]
+mod test {
+  use crate::{ops::Map, ErrComplete, Observable, Observer, Subject};
+  use std::cell::{Cell, RefCell};
+  use std::rc::Rc;
+
+  type Recorded = Rc<RefCell<Option<(Vec<i32>, ErrComplete<&'static str>)>>>;
+
+  struct Recorder {
+    values: RefCell<Vec<i32>>,
+    result: Recorded,
+  }
+
+  impl Observer for Recorder {
+    type Item = i32;
+    type Err = &'static str;
+
+    fn next(&self, v: i32) -> &Self {
+      self.values.borrow_mut().push(v);
+      self
+    }
+
+    fn complete(self) {
+      *self.result.borrow_mut() =
+        Some((self.values.into_inner(), ErrComplete::Complete));
+    }
+
+    fn err(self, err: &'static str) {
+      *self.result.borrow_mut() =
+        Some((self.values.into_inner(), ErrComplete::Err(err)));
+    }
+  }
+
+  fn new_recorder() -> (Recorder, Recorded) {
+    let result = Rc::new(RefCell::new(None));
+    let recorder = Recorder {
+      values: RefCell::new(vec![]),
+      result: result.clone(),
+    };
+    (recorder, result)
+  }
+
+  #[test]
+  fn observer_receives_all_notifications() {
+    let (recorder, result) = new_recorder();
+    let subject = Subject::new();
+    subject.clone().map(|v| v * 2).subscribe_observer(recorder);
+
+    subject.next(1).next(2);
+    assert!(result.borrow().is_none());
+    subject.clone().complete();
+    subject.next(3);
+    assert_eq!(*result.borrow(), Some((vec![2, 4], ErrComplete::Complete)));
+
+    let (recorder, result) = new_recorder();
+    let subject = Subject::new();
+    subject.clone().subscribe_observer(recorder);
+    subject.next(1);
+    subject.err("boom");
+    assert_eq!(*result.borrow(), Some((vec![1], ErrComplete::Err("boom"))));
+  }
+
+  #[test]
+  fn pipe_subject_into_subject() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let completed = Rc::new(Cell::new(false));
+    let source = Subject::new();
+    let target = Subject::new();
+
+    let r = received.clone();
+    let c = completed.clone();
+    target.clone().subscribe(
+      move |v| r.borrow_mut().push(v),
+      move |ec: &ErrComplete<()>| c.set(*ec == ErrComplete::Complete),
+    );
+    source.clone().map(|v| v + 1).subscribe_observer(target);
+
+    source.next(1).next(2);
+    source.complete();
+    assert_eq!(*received.borrow(), vec![2, 3]);
+    assert!(completed.get());
+  }
+
+  #[test]
+  fn subscribe_next() {
+    let mut received = vec![];
+    {
+      let subject = Subject::<_, ()>::new();
+      subject.clone().subscribe_next(|v| received.push(v));
+      subject.next(1).next(2);
+      subject.complete();
+    }
+    assert_eq!(received, vec![1, 2]);
+  }
+
+  #[test]
+  #[should_panic(expected = "unhandled error from an observable: \"boom\"")]
+  fn subscribe_next_panics_on_error() {
+    let subject = Subject::<i32, _>::new();
+    subject.clone().subscribe_next(|_| {});
+    subject.err("boom");
+  }
+
+  #[test]
+  fn subscribe_err_and_all() {
+    let notifications = RefCell::new(vec![]);
+    {
+      let failing = Subject::new();
+      failing.clone().subscribe_err(
+        |v| notifications.borrow_mut().push(format!("next {}", v)),
+        |e: &&str| notifications.borrow_mut().push(format!("err {}", e)),
+      );
+      failing.next(1);
+      failing.err("boom");
+
+      let completing = Subject::new();
+      completing.clone().subscribe_all(
+        |v| notifications.borrow_mut().push(format!("next {}", v)),
+        |e: &&str| notifications.borrow_mut().push(format!("err {}", e)),
+        || notifications.borrow_mut().push("complete".to_owned()),
+      );
+      completing.next(2);
+      completing.complete();
+    }
+    assert_eq!(
+      notifications.into_inner(),
+      vec!["next 1", "err boom", "next 2", "complete"]
+    );
+  }
+}
@@ -2,5 +2,45 @@ mod map;
 pub use map::Map;
 mod filter;
 pub use filter::Filter;
+mod scan;
+pub use scan::{Scan, ScanOp};
+mod reduce;
+pub use reduce::{Reduce, ReduceOp};
+mod notifier;
+pub use notifier::NotifierSubscription;
+mod take;
+pub use take::{Take, TakeOp};
+mod take_while;
+pub use take_while::{TakeWhile, TakeWhileOp};
+mod take_until;
+pub use take_until::{TakeUntil, TakeUntilOp};
+mod skip;
+pub use skip::{Skip, SkipOp};
+mod skip_while;
+pub use skip_while::{SkipWhile, SkipWhileOp};
+mod skip_until;
+pub use skip_until::{SkipUntil, SkipUntilOp};
+mod last;
+pub use last::{EmptyError, Last, LastOp, LastOrOp};
+mod element_at;
+pub use element_at::{ElementAt, ElementAtOp};
+mod distinct_until_changed;
+pub use distinct_until_changed::{
+  DistinctUntilChanged, DistinctUntilChangedOp, DistinctUntilKeyChangedOp,
+};
+mod distinct;
+pub use distinct::{Distinct, DistinctOp};
 mod merge;
 pub use merge::Merge;
+mod concat;
+pub use concat::{Concat, ConcatOp, ConcatSubscription};
+mod merge_all;
+pub use merge_all::{merge_all, MergeAllOp, MergeAllSubscription};
+mod flat_map;
+pub use flat_map::{FlatMap, FlatMapOp, FlatMapSubscription};
+mod switch_map;
+pub use switch_map::{SwitchMap, SwitchMapOp, SwitchMapSubscription};
+mod exhaust_map;
+pub use exhaust_map::{ExhaustMap, ExhaustMapOp};
+mod box_it;
+pub use box_it::{BoxIt, BoxObservable, ObservableBox};
@@ -0,0 +1,118 @@
+use crate::{subscription::BoxSubscription, ErrComplete, OState, Observable};
+
+type BoxNext<'a, T> = Box<dyn FnMut(T) -> OState + 'a>;
+type BoxErrComplete<'a, E> = Box<dyn FnMut(&ErrComplete<E>) + 'a>;
+
+/// An object safe version of `Observable`, every observable implements it,
+/// so observables of different types emitting the same items can be boxed
+/// into a `BoxObservable`.
+pub trait ObservableBox<'a, T, E> {
+  fn subscribe_box(
+    self: Box<Self>, next: BoxNext<'a, T>,
+    err_or_complete: BoxErrComplete<'a, E>,
+  ) -> BoxSubscription<'a>;
+}
+
+impl<'a, O> ObservableBox<'a, O::Item, O::Err> for O
+where
+  O: Observable<'a> + 'a,
+  O::Unsubscribe: 'a,
+{
+  fn subscribe_box(
+    self: Box<Self>, next: BoxNext<'a, O::Item>,
+    err_or_complete: BoxErrComplete<'a, O::Err>,
+  ) -> BoxSubscription<'a> {
+    Box::new((*self).subscribe_return_state(next, err_or_complete))
+  }
+}
+
+pub type BoxObservable<'a, T, E> = Box<dyn ObservableBox<'a, T, E> + 'a>;
+
+impl<'a, T: 'a, E: 'a> Observable<'a> for BoxObservable<'a, T, E> {
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = BoxSubscription<'a>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    self.subscribe_box(Box::new(next), Box::new(err_or_complete))
+  }
+}
+
+/// Erase the type of an observable, so observables built in different ways
+/// can be stored or merged together.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   ops::{BoxIt, BoxObservable, Filter, Map}, ErrComplete, Observable,
+/// #   Observer, Subject,
+/// # };
+/// let numbers = Subject::<i32, ()>::new();
+/// let streams: Vec<BoxObservable<i32, ()>> = vec![
+///   numbers.clone().box_it(),
+///   numbers.clone().map(|v| v * 10).box_it(),
+///   numbers.clone().filter(|v| *v > 1).box_it(),
+/// ];
+/// ```
+pub trait BoxIt<'a, T, E> {
+  fn box_it(self) -> BoxObservable<'a, T, E>
+  where
+    Self: Sized + ObservableBox<'a, T, E> + 'a,
+  {
+    Box::new(self)
+  }
+}
+
+impl<'a, T, E, O> BoxIt<'a, T, E> for O where
+  O: Observable<'a, Item = T, Err = E>
+{
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    ops::{BoxIt, Filter, Map},
+    ErrComplete, Observable, Observer, Subject, Subscription,
+  };
+  use std::cell::RefCell;
+  use std::rc::Rc;
+
+  #[test]
+  fn boxed_observables_of_different_types() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let numbers = Subject::<i32, ()>::new();
+    let streams = vec![
+      numbers.clone().map(|v| v * 10).box_it(),
+      numbers.clone().filter(|v| *v > 1).box_it(),
+    ];
+
+    let subscriptions = streams
+      .into_iter()
+      .map(|s| {
+        let r = received.clone();
+        s.subscribe(move |v| r.borrow_mut().push(v), |_| {})
+      })
+      .collect::<Vec<_>>();
+    numbers.next(1);
+    numbers.next(2);
+    assert_eq!(*received.borrow(), vec![10, 20, 2]);
+
+    subscriptions.into_iter().for_each(|s| s.unsubscribe());
+    numbers.next(3);
+    assert_eq!(*received.borrow(), vec![10, 20, 2]);
+
+    let mut completed = false;
+    numbers.clone().complete();
+    numbers
+      .box_it()
+      .subscribe(|_| {}, |_: &ErrComplete<()>| completed = true);
+    assert!(completed);
+  }
+}
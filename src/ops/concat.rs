@@ -0,0 +1,217 @@
+use crate::{
+  creation::{from_iter, FromIterOp},
+  ErrComplete, OState, Observable, Subscription,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// Sequential composition: emit all the items of an observable, then all
+/// the items of another one.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::{from_iter, of}, ops::Concat, Observable};
+/// let mut received = vec![];
+/// from_iter::<_, ()>(vec![2, 3])
+///   .concat(of(4))
+///   .start_with(vec![0, 1])
+///   .end_with(vec![5])
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![0, 1, 2, 3, 4, 5]);
+/// ```
+pub trait Concat<'a, T> {
+  /// Emit the items of this observable, then subscribe `other` once this
+  /// observable has completed and emit its items. A failure of this
+  /// observable fails the stream without subscribing `other`.
+  fn concat<S>(self, other: S) -> ConcatOp<Self, S>
+  where
+    Self: Sized,
+    S: Observable<'a, Item = T>,
+  {
+    ConcatOp {
+      source1: self,
+      source2: other,
+    }
+  }
+
+  /// Emit `values` before the items of this observable.
+  fn start_with<I>(
+    self, values: I,
+  ) -> ConcatOp<FromIterOp<I::IntoIter, Self::Err>, Self>
+  where
+    Self: Observable<'a, Item = T>,
+    I: IntoIterator<Item = T>,
+  {
+    from_iter(values).concat(self)
+  }
+
+  /// Emit `values` after this observable has completed.
+  fn end_with<I>(
+    self, values: I,
+  ) -> ConcatOp<Self, FromIterOp<I::IntoIter, Self::Err>>
+  where
+    Self: Observable<'a, Item = T>,
+    I: IntoIterator<Item = T>,
+  {
+    self.concat(from_iter(values))
+  }
+}
+
+impl<'a, T, O> Concat<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct ConcatOp<S1, S2> {
+  source1: S1,
+  source2: S2,
+}
+
+impl<'a, T, S1, S2, E> Observable<'a> for ConcatOp<S1, S2>
+where
+  S1: Observable<'a, Item = T, Err = E>,
+  S2: Observable<'a, Item = T, Err = E> + 'a,
+  S1::Unsubscribe: 'a,
+  S2::Unsubscribe: 'a,
+{
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = ConcatSubscription<S1::Unsubscribe, S2::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let next = Rc::new(RefCell::new(next));
+    let err_or_complete = Rc::new(RefCell::new(err_or_complete));
+    let subscription = ConcatSubscription {
+      subscription1: Rc::new(RefCell::new(None)),
+      subscription2: Rc::new(RefCell::new(None)),
+      stopped: Rc::new(Cell::new(false)),
+    };
+
+    let next1 = next.clone();
+    let stopped = subscription.stopped.clone();
+    let slot2 = subscription.subscription2.clone();
+    let mut source2 = Some(self.source2);
+    let s1 = self.source1.subscribe_return_state(
+      move |v| (*next1.borrow_mut())(v),
+      move |ec| match ec {
+        ErrComplete::Err(_) => (*err_or_complete.borrow_mut())(ec),
+        ErrComplete::Complete => {
+          // unsubscribed meanwhile.
+          if stopped.get() {
+            return;
+          }
+          if let Some(source2) = source2.take() {
+            let next2 = next.clone();
+            let ec2 = err_or_complete.clone();
+            let s2 = source2.subscribe_return_state(
+              move |v| (*next2.borrow_mut())(v),
+              move |ec| (*ec2.borrow_mut())(ec),
+            );
+            *slot2.borrow_mut() = Some(s2);
+          }
+        }
+      },
+    );
+    if subscription.subscription2.borrow().is_none() {
+      *subscription.subscription1.borrow_mut() = Some(s1);
+    } else {
+      // the first source is already done.
+      s1.unsubscribe();
+    }
+    subscription
+  }
+}
+
+pub struct ConcatSubscription<S1, S2> {
+  subscription1: Rc<RefCell<Option<S1>>>,
+  subscription2: Rc<RefCell<Option<S2>>>,
+  stopped: Rc<Cell<bool>>,
+}
+
+impl<S1, S2> Subscription for ConcatSubscription<S1, S2>
+where
+  S1: Subscription,
+  S2: Subscription,
+{
+  fn unsubscribe(self) {
+    // the second source must not be subscribed any more.
+    self.stopped.set(true);
+    if let Some(s) = self.subscription1.borrow_mut().take() {
+      s.unsubscribe();
+    }
+    if let Some(s) = self.subscription2.borrow_mut().take() {
+      s.unsubscribe();
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, of, throw},
+    ops::{Concat, Map, Take},
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot, TestObserver},
+    Observer, Subject, Subscription,
+  };
+  use std::cell::Cell;
+  use std::rc::Rc;
+
+  #[test]
+  fn subscribes_second_after_first_completes() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3), ('d', 4)];
+    let first = cold::<_, ()>(&scheduler, "-a-b|", &values);
+    let second = hot(&scheduler, "-c---d-|", &values);
+    // `c` is emitted before `second` is subscribed, and lost.
+    expect_observable(&scheduler, first.concat(second))
+      .to_be("-a-b-d-|", &values);
+  }
+
+  #[test]
+  fn error_skips_second() {
+    let subscribed = Rc::new(Cell::new(false));
+    let s = subscribed.clone();
+    let observer = TestObserver::new();
+    observer.subscribe_to(throw("boom").concat(of(1).map(move |v| {
+      s.set(true);
+      v
+    })));
+    observer.assert_values(&[]);
+    observer.assert_error(&"boom");
+    assert!(!subscribed.get());
+  }
+
+  #[test]
+  fn unsubscribe_before_second() {
+    let first = Subject::<i32, ()>::new();
+    let second = Subject::new();
+    let observer = TestObserver::new();
+    let subscription =
+      observer.subscribe_to(first.clone().concat(second.clone()));
+    first.next(1);
+    subscription.unsubscribe();
+
+    first.clone().complete();
+    second.next(2);
+    observer.assert_values(&[1]);
+    observer.assert_no_terminal();
+  }
+
+  #[test]
+  fn start_and_end_with() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(of(2).start_with(vec![0, 1]).end_with(3..5));
+    observer.assert_values(&[0, 1, 2, 3, 4]);
+    observer.assert_completed();
+
+    let endless = TestObserver::<_, ()>::new();
+    endless.subscribe_to(from_iter(0..).end_with(vec![-1]).take(2));
+    endless.assert_values(&[0, 1]);
+    endless.assert_completed();
+  }
+}
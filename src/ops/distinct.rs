@@ -0,0 +1,139 @@
+use crate::{
+  creation::{never, NeverOp},
+  ops::{
+    notifier::{subscribe_with_notifier, Notified},
+    NotifierSubscription,
+  },
+  ErrComplete, OState, Observable,
+};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// Ignores the items equal to any item emitted before them.
+///
+/// Every emitted item is kept in a `HashSet`, which grows as long as the
+/// source emits new items, unless a flush notifier clears it.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::Distinct, Observable};
+/// let mut received = vec![];
+/// from_iter::<_, ()>(vec![1, 2, 1, 3, 2])
+///   .distinct()
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![1, 2, 3]);
+/// ```
+pub trait Distinct<'a, T> {
+  fn distinct(self) -> DistinctOp<Self, NeverOp<(), Self::Err>>
+  where
+    Self: Observable<'a>,
+  {
+    self.distinct_flushed_by(never())
+  }
+
+  /// Forget the items emitted so far every time `flushes` emits, an error of
+  /// `flushes` fails the stream.
+  fn distinct_flushed_by<F>(self, flushes: F) -> DistinctOp<Self, F>
+  where
+    Self: Sized,
+  {
+    DistinctOp {
+      source: self,
+      flushes,
+    }
+  }
+}
+
+impl<'a, T, O> Distinct<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct DistinctOp<S, F> {
+  source: S,
+  flushes: F,
+}
+
+impl<'a, S, F> Observable<'a> for DistinctOp<S, F>
+where
+  S: Observable<'a>,
+  S::Item: Clone + Eq + Hash + 'a,
+  S::Unsubscribe: 'a,
+  F: Observable<'a, Err = S::Err>,
+  F::Unsubscribe: 'a,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = NotifierSubscription<S::Unsubscribe, F::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let seen = Rc::new(RefCell::new(HashSet::new()));
+    let on_flush = seen.clone();
+    subscribe_with_notifier(
+      self.source,
+      self.flushes,
+      move |v: &S::Item| seen.borrow_mut().insert(v.clone()),
+      move || {
+        on_flush.borrow_mut().clear();
+        Notified::Listen
+      },
+      next,
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    ops::Distinct,
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot},
+  };
+
+  #[test]
+  fn suppresses_items_seen_before() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3)];
+    let source = hot::<_, ()>(&scheduler, "-a-b-a-c-b-c-a-|", &values);
+    expect_observable(&scheduler, source.distinct())
+      .to_be("-a-b---c-------|", &values);
+  }
+
+  #[test]
+  fn error_passes_through() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2)];
+    let source = hot(&scheduler, "-a-a-b-#", &values).with_error("boom");
+    expect_observable(&scheduler, source.distinct())
+      .with_error("boom")
+      .to_be("-a---b-#", &values);
+  }
+
+  #[test]
+  fn flush_forgets_seen_items() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('x', 0)];
+    let source = hot::<_, ()>(&scheduler, "-a-b-a-b-a-|", &values);
+    let flushes = cold(&scheduler, "------x", &values);
+    expect_observable(&scheduler, source.distinct_flushed_by(flushes))
+      .to_be("-a-b---b-a-|", &values);
+  }
+
+  #[test]
+  fn flushes_error_fails() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1)];
+    let source = hot(&scheduler, "-a-a-|", &values);
+    let flushes = cold(&scheduler, "--#", &values).with_error("boom");
+    expect_observable(&scheduler, source.distinct_flushed_by(flushes))
+      .with_error("boom")
+      .to_be("-a#", &values);
+  }
+}
@@ -0,0 +1,163 @@
+use crate::{ErrComplete, OState, Observable};
+
+/// Ignores the items equal to the item emitted just before them.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::from_iter, ops::DistinctUntilChanged, Observable,
+/// # };
+/// let mut received = vec![];
+/// from_iter::<_, ()>(vec![1, 1, 2, 2, 1, 3, 3])
+///   .distinct_until_changed()
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![1, 2, 1, 3]);
+/// ```
+pub trait DistinctUntilChanged<'a, T> {
+  fn distinct_until_changed(
+    self,
+  ) -> DistinctUntilChangedOp<Self, fn(&T, &T) -> bool>
+  where
+    Self: Sized,
+    T: PartialEq + 'a,
+  {
+    self.distinct_until_changed_by(T::eq)
+  }
+
+  /// Compare the items by the key `key` extracts from them, only the key of
+  /// the previous item is kept.
+  fn distinct_until_changed_by_key<K, F>(
+    self, key: F,
+  ) -> DistinctUntilKeyChangedOp<Self, F>
+  where
+    Self: Sized,
+    K: PartialEq,
+    F: FnMut(&T) -> K + 'a,
+  {
+    DistinctUntilKeyChangedOp { source: self, key }
+  }
+
+  /// Compare the items by `same`, returning true if an item, the first
+  /// argument, is the same as the previous one, the second argument.
+  fn distinct_until_changed_by<F>(
+    self, same: F,
+  ) -> DistinctUntilChangedOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(&T, &T) -> bool + 'a,
+  {
+    DistinctUntilChangedOp { source: self, same }
+  }
+}
+
+impl<'a, T, O> DistinctUntilChanged<'a, T> for O where
+  O: Observable<'a, Item = T>
+{
+}
+
+pub struct DistinctUntilChangedOp<S, F> {
+  source: S,
+  same: F,
+}
+
+impl<'a, S, F> Observable<'a> for DistinctUntilChangedOp<S, F>
+where
+  S: Observable<'a>,
+  S::Item: Clone + 'a,
+  F: 'a + FnMut(&S::Item, &S::Item) -> bool,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut same = self.same;
+    let mut previous = None;
+    self.source.subscribe_return_state(
+      move |v| {
+        if let Some(p) = &previous {
+          if same(&v, p) {
+            return OState::Next;
+          }
+        }
+        previous = Some(v.clone());
+        next(v)
+      },
+      err_or_complete,
+    )
+  }
+}
+
+pub struct DistinctUntilKeyChangedOp<S, F> {
+  source: S,
+  key: F,
+}
+
+impl<'a, S, F, K> Observable<'a> for DistinctUntilKeyChangedOp<S, F>
+where
+  S: Observable<'a>,
+  K: PartialEq + 'a,
+  F: 'a + FnMut(&S::Item) -> K,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut key = self.key;
+    let mut previous = None;
+    self.source.subscribe_return_state(
+      move |v| {
+        let k = key(&v);
+        if previous.as_ref() == Some(&k) {
+          return OState::Next;
+        }
+        previous = Some(k);
+        next(v)
+      },
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::from_iter, ops::DistinctUntilChanged, testing::TestObserver,
+  };
+
+  #[test]
+  fn by_key() {
+    let observer = TestObserver::<_, ()>::new();
+    let readings = vec![("a", 1), ("b", 1), ("c", 2), ("d", 1)];
+    observer.subscribe_to(
+      from_iter(readings).distinct_until_changed_by_key(|(_, v)| *v),
+    );
+    observer.assert_values(&[("a", 1), ("c", 2), ("d", 1)]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn by_comparator() {
+    let observer = TestObserver::<_, ()>::new();
+    let readings = vec![1.0, 1.05, 1.2, 1.22, 1.0];
+    observer.subscribe_to(
+      from_iter(readings)
+        .distinct_until_changed_by(|a: &f64, b| (a - b).abs() < 0.1),
+    );
+    observer.assert_values(&[1.0, 1.2, 1.0]);
+  }
+}
@@ -0,0 +1,107 @@
+use crate::{ops::EmptyError, ErrComplete, OState, Observable};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Emits only the item at `index`, counted from zero, then completes and
+/// stops the source. A source completing before fails with an `EmptyError`.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::from_iter,
+/// #   ops::{ElementAt, EmptyError},
+/// #   Observable,
+/// # };
+/// let mut received = vec![];
+/// from_iter::<_, EmptyError>(vec!['a', 'b', 'c'])
+///   .element_at(1)
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec!['b']);
+/// ```
+pub trait ElementAt<'a> {
+  fn element_at(self, index: usize) -> ElementAtOp<Self>
+  where
+    Self: Sized,
+  {
+    ElementAtOp {
+      source: self,
+      index,
+    }
+  }
+}
+
+impl<'a, O> ElementAt<'a> for O where O: Observable<'a> {}
+
+pub struct ElementAtOp<S> {
+  source: S,
+  index: usize,
+}
+
+impl<'a, S> Observable<'a> for ElementAtOp<S>
+where
+  S: Observable<'a>,
+  S::Err: From<EmptyError>,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let err_or_complete = Rc::new(RefCell::new(err_or_complete));
+    let complete = err_or_complete.clone();
+    let mut remaining = self.index;
+    self.source.subscribe_return_state(
+      move |v| {
+        if remaining > 0 {
+          remaining -= 1;
+          return OState::Next;
+        }
+        if next(v) == OState::Next {
+          (*complete.borrow_mut())(&ErrComplete::Complete);
+        }
+        OState::Complete
+      },
+      move |ec| {
+        let mut err_or_complete = err_or_complete.borrow_mut();
+        match ec {
+          ErrComplete::Complete => {
+            (*err_or_complete)(&ErrComplete::Err(EmptyError.into()))
+          }
+          ErrComplete::Err(_) => (*err_or_complete)(ec),
+        }
+      },
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, of},
+    ops::{ElementAt, EmptyError},
+    testing::TestObserver,
+  };
+
+  #[test]
+  fn emits_item_at_index() {
+    let observer = TestObserver::<_, EmptyError>::new();
+    observer.subscribe_to(from_iter(10..).element_at(2));
+    observer.assert_values(&[12]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn out_of_range_fails() {
+    let observer = TestObserver::<_, EmptyError>::new();
+    observer.subscribe_to(of(1).element_at(1));
+    observer.assert_values(&[]);
+    observer.assert_error(&EmptyError);
+  }
+}
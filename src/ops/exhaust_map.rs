@@ -0,0 +1,123 @@
+use crate::{
+  ops::{switch_map::subscribe_one_inner, SwitchMapSubscription},
+  ErrComplete, OState, Observable,
+};
+
+/// Maps each item to an inner observable and emits its items, ignoring the
+/// items arriving while an inner observable is still active, so inner
+/// observables never overlap.
+///
+/// The stream completes after the source and the active inner observable
+/// have completed, and fails as soon as either fails.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{ops::ExhaustMap, Observable, Observer, Subject};
+/// # use std::cell::RefCell;
+/// let saved = RefCell::new(vec![]);
+/// let clicks = Subject::<&str, ()>::new();
+/// let request = Subject::<&str, ()>::new();
+/// let r = request.clone();
+/// clicks
+///   .clone()
+///   .exhaust_map(move |_| r.clone())
+///   .subscribe_next(|v| saved.borrow_mut().push(v));
+///
+/// clicks.next("save");
+/// // clicked again while saving, ignored.
+/// clicks.next("save");
+/// request.next("saved");
+/// assert_eq!(*saved.borrow(), vec!["saved"]);
+/// ```
+pub trait ExhaustMap<'a, T> {
+  fn exhaust_map<O, F>(self, f: F) -> ExhaustMapOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(T) -> O + 'a,
+  {
+    ExhaustMapOp {
+      source: self,
+      func: f,
+    }
+  }
+}
+
+impl<'a, T, O> ExhaustMap<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct ExhaustMapOp<S, F> {
+  source: S,
+  func: F,
+}
+
+impl<'a, S, F, O> Observable<'a> for ExhaustMapOp<S, F>
+where
+  S: Observable<'a>,
+  S::Unsubscribe: 'a,
+  F: 'a + FnMut(S::Item) -> O,
+  O: Observable<'a, Err = S::Err>,
+  O::Unsubscribe: 'a,
+{
+  type Item = O::Item;
+  type Err = S::Err;
+  type Unsubscribe = SwitchMapSubscription<S::Unsubscribe, O::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    subscribe_one_inner(self.source, self.func, true, next, err_or_complete)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, of},
+    ops::{ExhaustMap, Take},
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot, TestObserver},
+    Observer, Subject, Subscription,
+  };
+
+  #[test]
+  fn ignores_items_while_inner_active() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3)];
+    let commands = hot::<_, ()>(&scheduler, "-a-b---c--|", &values);
+    let s = scheduler.clone();
+    let results = commands.exhaust_map(move |v| cold(&s, "-x-x|", &[('x', v)]));
+    // `b` arrives while the inner observable of `a` runs.
+    expect_observable(&scheduler, results).to_be("--a-a---c-c|", &values);
+  }
+
+  #[test]
+  fn completion_waits_for_active_inner() {
+    let outer = Subject::<_, ()>::new();
+    let inner = Subject::new();
+    let for_outer = inner.clone();
+    let observer = TestObserver::<i32, ()>::new();
+    let subscription = observer
+      .subscribe_to(outer.clone().exhaust_map(move |_| for_outer.clone()));
+
+    outer.next(());
+    outer.clone().complete();
+    inner.next(1);
+    observer.assert_no_terminal();
+    inner.clone().complete();
+    observer.assert_values(&[1]);
+    observer.assert_completed();
+    subscription.unsubscribe();
+  }
+
+  #[test]
+  fn synchronous_inners_never_overlap() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..).exhaust_map(of).take(3));
+    observer.assert_values(&[0, 1, 2]);
+    observer.assert_completed();
+  }
+}
@@ -1,4 +1,4 @@
-use crate::{ErrComplete, Observable};
+use crate::{ErrComplete, OState, Observable};
 
 /// Emit only those items from an Observable that pass a predicate test
 /// # Example
@@ -13,18 +13,17 @@ use crate::{ErrComplete, Observable};
 /// let coll_clone = coll.clone();
 ///
 /// subject.clone().filter(|v| *v % 2 == 0).subscribe(move |v| {
-///    coll_clone.borrow_mut().push(*v);
+///    coll_clone.borrow_mut().push(v);
 /// },
 /// |ec: &ErrComplete<()>| {});
-
+///
 /// (0..10).into_iter().for_each(|v| {
 ///    subject.next(v);
 /// });
-
+///
 /// // only even numbers received.
 /// assert_eq!(coll.borrow().clone(), vec![0, 2, 4, 6, 8]);
 /// ```
-
 pub trait Filter<'a, T> {
   fn filter<F>(self, filter: F) -> FilterOp<Self, F>
   where
@@ -54,18 +53,20 @@ where
   type Unsubscribe = S::Unsubscribe;
   type Err = S::Err;
 
-  fn subscribe<N, EC>(
+  fn subscribe_return_state<N, EC>(
     self, mut next: N, err_or_complete: EC,
   ) -> Self::Unsubscribe
   where
-    N: 'a + FnMut(Self::Item),
+    N: 'a + FnMut(Self::Item) -> OState,
     EC: 'a + FnMut(&ErrComplete<Self::Err>),
   {
     let mut filter = self.filter;
-    self.source.subscribe(
+    self.source.subscribe_return_state(
       move |v| {
         if filter(&v) {
-          next(v);
+          next(v)
+        } else {
+          OState::Next
         }
       },
       err_or_complete,
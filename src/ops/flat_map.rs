@@ -0,0 +1,380 @@
+use crate::{
+  ops::{merge_all::MergeAllState, MergeAllSubscription},
+  ErrComplete, OState, Observable, Subscription,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Maps each item to an inner observable, and merges the emissions of all
+/// the inner observables, also known as `merge_map`.
+///
+/// The stream completes after the source and every inner observable have
+/// completed, and fails as soon as any of them fails, unsubscribing all the
+/// others. Unsubscribing the stream unsubscribes the source and every inner
+/// observable.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::FlatMap, Observable};
+/// let mut received = vec![];
+/// from_iter::<_, ()>(1..4)
+///   .flat_map(|v| from_iter(vec![v; v]))
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![1, 2, 2, 3, 3, 3]);
+/// ```
+pub trait FlatMap<'a, T> {
+  fn flat_map<O, F>(self, f: F) -> FlatMapOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(T) -> O + 'a,
+  {
+    FlatMapOp {
+      source: self,
+      func: f,
+      max_concurrent: usize::MAX,
+    }
+  }
+
+  /// Maps each item to an inner observable, and emits the inner observables
+  /// one after the other, preserving their order: an item is mapped, and
+  /// its inner observable subscribed, only after the previous inner
+  /// observable has completed, the items arriving meanwhile are queued. The
+  /// same as `flat_map` with a `max_concurrent(1)` limit.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// # use rx_rs::{creation::from_iter, ops::FlatMap, Observable, Observer,
+  /// #   Subject};
+  /// # use std::cell::RefCell;
+  /// let received = RefCell::new(vec![]);
+  /// let responses = vec![Subject::<_, ()>::new(), Subject::new()];
+  /// let r = responses.clone();
+  /// from_iter(0..2)
+  ///   .concat_map(move |i| r[i].clone())
+  ///   .subscribe_next(|v| received.borrow_mut().push(v));
+  ///
+  /// // the second response is not subscribed until the first completes.
+  /// responses[1].next("lost");
+  /// responses[0].next("first");
+  /// responses[0].clone().complete();
+  /// responses[1].next("second");
+  /// assert_eq!(*received.borrow(), vec!["first", "second"]);
+  /// ```
+  fn concat_map<O, F>(self, f: F) -> FlatMapOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(T) -> O + 'a,
+  {
+    self.flat_map(f).max_concurrent(1)
+  }
+}
+
+impl<'a, T, O> FlatMap<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct FlatMapOp<S, F> {
+  source: S,
+  func: F,
+  max_concurrent: usize,
+}
+
+impl<S, F> FlatMapOp<S, F> {
+  /// Subscribe at most `limit` inner observables at the same time. The
+  /// items of the source arriving meanwhile are queued, and mapped to their
+  /// inner observable in order, only as the active ones complete.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `limit` is 0.
+  pub fn max_concurrent(mut self, limit: usize) -> Self {
+    assert!(
+      limit > 0,
+      "subscribe at least one inner observable at a time"
+    );
+    self.max_concurrent = limit;
+    self
+  }
+}
+
+impl<'a, S, F, O> Observable<'a> for FlatMapOp<S, F>
+where
+  S: Observable<'a>,
+  S::Item: 'a,
+  S::Unsubscribe: 'a,
+  F: 'a + FnMut(S::Item) -> O,
+  O: Observable<'a, Err = S::Err> + 'a,
+  O::Item: 'a,
+  O::Unsubscribe: 'a,
+{
+  type Item = O::Item;
+  type Err = S::Err;
+  type Unsubscribe =
+    FlatMapSubscription<S::Unsubscribe, S::Item, O::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, mut err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let outer = Rc::new(RefCell::new(None));
+    // the inners stop by themselves, the source needs to be told.
+    let outer_on_next = outer.clone();
+    let outer_on_ec = outer.clone();
+    let inners = MergeAllState::new(
+      self.max_concurrent,
+      self.func,
+      move |v| {
+        let state = next(v);
+        if state == OState::Complete {
+          unsubscribe_outer(&outer_on_next);
+        }
+        state
+      },
+      move |ec: &ErrComplete<S::Err>| {
+        if let ErrComplete::Err(_) = ec {
+          unsubscribe_outer(&outer_on_ec);
+        }
+        err_or_complete(ec);
+      },
+    );
+
+    let on_next = inners.clone();
+    let on_ec = inners.clone();
+    let subscription = self.source.subscribe_return_state(
+      move |v| {
+        if !on_next.is_stopped() {
+          on_next.add_source(v);
+        }
+        if on_next.is_stopped() {
+          OState::Complete
+        } else {
+          OState::Next
+        }
+      },
+      move |ec| match ec {
+        ErrComplete::Complete => on_ec.no_more_sources(),
+        ErrComplete::Err(_) => on_ec.error(ec),
+      },
+    );
+    if inners.is_stopped() {
+      subscription.unsubscribe();
+    } else {
+      *outer.borrow_mut() = Some(subscription);
+    }
+
+    FlatMapSubscription {
+      outer,
+      inners: inners.subscription(),
+    }
+  }
+}
+
+fn unsubscribe_outer<S: Subscription>(outer: &RefCell<Option<S>>) {
+  let subscription = outer.borrow_mut().take();
+  if let Some(s) = subscription {
+    s.unsubscribe();
+  }
+}
+
+/// Owns the subscription of the source and of every inner observable, and
+/// the items of the source waiting for a free slot.
+pub struct FlatMapSubscription<S, T, I> {
+  outer: Rc<RefCell<Option<S>>>,
+  inners: MergeAllSubscription<T, I>,
+}
+
+impl<S, T, I> Subscription for FlatMapSubscription<S, T, I>
+where
+  S: Subscription,
+  I: Subscription,
+{
+  fn unsubscribe(self) {
+    unsubscribe_outer(&self.outer);
+    self.inners.unsubscribe();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, of, throw},
+    ops::{BoxIt, FlatMap, Map, Take},
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot, TestObserver, FRAME},
+    Observer, Subject, Subscription,
+  };
+  use std::cell::{Cell, RefCell};
+  use std::rc::Rc;
+  use std::time::Duration;
+
+  #[test]
+  fn merges_inner_observables() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('x', 10), ('y', 20)];
+    let clicks = hot::<_, ()>(&scheduler, "-a--b-|", &values);
+    let s = scheduler.clone();
+    let requests = clicks
+      .flat_map(move |v| cold(&s, "-x-x|", &[('x', v * 10)]).map(|v| v * 2));
+    let values = [('x', 20), ('y', 40)];
+    expect_observable(&scheduler, requests).to_be("--x-xy-y|", &values);
+  }
+
+  #[test]
+  fn max_concurrent_queues_inners() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2)];
+    let projected = Rc::new(RefCell::new(vec![]));
+    let s = scheduler.clone();
+    let p = projected.clone();
+    let source = cold::<_, ()>(&scheduler, "ab|", &values)
+      .flat_map(move |v| {
+        p.borrow_mut().push((v, s.now()));
+        cold(&s, "-a-a|", &[('a', v)])
+      })
+      .max_concurrent(1);
+    expect_observable(&scheduler, source).to_be("-a-a-b-b|", &values);
+    // `b` is mapped only once the inner observable of `a` completes.
+    assert_eq!(
+      *projected.borrow(),
+      vec![(1, Duration::default()), (2, FRAME * 4)]
+    );
+  }
+
+  #[test]
+  fn many_queued_synchronous_inners() {
+    let first = Subject::new();
+    let f = first.clone();
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(
+      from_iter(0..=100_000)
+        .flat_map(move |i| match i {
+          0 => f.clone().box_it(),
+          i => of(i).box_it(),
+        })
+        .max_concurrent(1),
+    );
+
+    // the inners queued behind the first one run one after the other.
+    first.complete();
+    observer.assert_values(&(1..=100_000).collect::<Vec<_>>());
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn unsubscribe_tears_down_all() {
+    let outer = Subject::<i32, ()>::new();
+    let inners = (0..2).map(|_| Subject::new()).collect::<Vec<_>>();
+    let emitted = Rc::new(Cell::new(0));
+
+    let for_inners = inners.clone();
+    let e = emitted.clone();
+    let observer = TestObserver::new();
+    let subscription =
+      observer.subscribe_to(outer.clone().flat_map(move |i| {
+        let e = e.clone();
+        for_inners[i as usize].clone().map(move |v: i32| {
+          e.set(e.get() + 1);
+          v
+        })
+      }));
+    outer.next(0).next(1);
+    inners[0].next(1);
+    inners[1].next(2);
+    subscription.unsubscribe();
+
+    outer.next(0);
+    inners[0].next(3);
+    inners[1].next(4);
+    observer.assert_values(&[1, 2]);
+    assert_eq!(emitted.get(), 2);
+  }
+
+  #[test]
+  fn completes_after_outer_and_inners() {
+    let outer = Subject::<_, ()>::new();
+    let inner = Subject::new();
+    let for_outer = inner.clone();
+    let observer = TestObserver::<i32, ()>::new();
+    observer.subscribe_to(outer.clone().flat_map(move |_| for_outer.clone()));
+
+    outer.next(());
+    outer.clone().complete();
+    observer.assert_no_terminal();
+    inner.complete();
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn inner_error_stops_outer() {
+    let outer = Subject::new();
+    let observer = TestObserver::<i32, _>::new();
+    observer.subscribe_to(outer.clone().flat_map(|v| {
+      if v < 2 {
+        of(v).box_it()
+      } else {
+        throw("boom").box_it()
+      }
+    }));
+    outer.next(1).next(2).next(3);
+    observer.assert_values(&[1]);
+    observer.assert_error(&"boom");
+  }
+
+  #[test]
+  fn take_stops_endless_outer() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..).flat_map(|v| of(v * 2)).take(3));
+    observer.assert_values(&[0, 2, 4]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn concat_map_runs_one_inner_at_a_time() {
+    let commands = (0..3).map(|_| Subject::new()).collect::<Vec<_>>();
+    let projected = Rc::new(Cell::new(0));
+    let c = commands.clone();
+    let p = projected.clone();
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..3).concat_map(move |i| {
+      p.set(p.get() + 1);
+      c[i].clone()
+    }));
+    assert_eq!(projected.get(), 1);
+
+    // the next command isn't started, so it misses this one.
+    commands[1].next("lost");
+    commands[0].next("run 0");
+    commands[0].clone().complete();
+    assert_eq!(projected.get(), 2);
+    commands[1].next("run 1");
+    commands[1].clone().complete();
+    assert_eq!(projected.get(), 3);
+    observer.assert_no_terminal();
+    commands[2].clone().complete();
+
+    observer.assert_values(&["run 0", "run 1"]);
+    observer.assert_completed();
+    assert_eq!(projected.get(), 3);
+  }
+
+  #[test]
+  fn concat_map_keeps_order_behind_async_inner() {
+    let first = Subject::new();
+    let f = first.clone();
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..10_000).concat_map(move |i| match i {
+      0 => f.clone().box_it(),
+      i => from_iter(vec![i * 2, i * 2 + 1]).box_it(),
+    }));
+
+    first.next(0);
+    first.next(1);
+    observer.assert_values(&[0, 1]);
+    first.complete();
+    observer.assert_values(&(0..20_000).collect::<Vec<_>>());
+    observer.assert_completed();
+  }
+}
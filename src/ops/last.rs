@@ -0,0 +1,215 @@
+use crate::{ErrComplete, OState, Observable};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// The error of an operator needing an item the source completed without,
+/// like `last` of an empty source.
+///
+/// Observables using it need an error type converting from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmptyError;
+
+impl fmt::Display for EmptyError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("the source completed without the expected item")
+  }
+}
+
+impl std::error::Error for EmptyError {}
+
+/// Emits only the last item of the source, when it completes.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::{empty, from_iter},
+/// #   ops::{EmptyError, Last},
+/// #   ErrComplete, Observable,
+/// # };
+/// let mut received = vec![];
+/// from_iter::<_, EmptyError>(0..5)
+///   .last()
+///   .subscribe_next(|v| received.push(v));
+/// empty::<_, ()>().last_or(-1).subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![4, -1]);
+///
+/// let mut failed = false;
+/// empty::<i32, EmptyError>()
+///   .last()
+///   .subscribe(|_| {}, |ec| failed = ec == &ErrComplete::Err(EmptyError));
+/// assert!(failed);
+/// ```
+pub trait Last<'a, T> {
+  /// An empty source fails with an `EmptyError`.
+  fn last(self) -> LastOp<Self>
+  where
+    Self: Sized,
+  {
+    LastOp { source: self }
+  }
+
+  /// An empty source emits `default`.
+  fn last_or(self, default: T) -> LastOrOp<Self, T>
+  where
+    Self: Sized,
+  {
+    LastOrOp {
+      source: self,
+      default,
+    }
+  }
+}
+
+impl<'a, T, O> Last<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct LastOp<S> {
+  source: S,
+}
+
+impl<'a, S> Observable<'a> for LastOp<S>
+where
+  S: Observable<'a>,
+  S::Item: 'a,
+  S::Err: From<EmptyError>,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    subscribe_last(self.source, next, err_or_complete, || {
+      Err(EmptyError.into())
+    })
+  }
+}
+
+pub struct LastOrOp<S, T> {
+  source: S,
+  default: T,
+}
+
+impl<'a, S> Observable<'a> for LastOrOp<S, S::Item>
+where
+  S: Observable<'a>,
+  S::Item: 'a,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let default = self.default;
+    subscribe_last(self.source, next, err_or_complete, move || Ok(default))
+  }
+}
+
+/// Keep the last item, emit it on completion, or what `on_empty` returns if
+/// there is none.
+fn subscribe_last<'a, S, N, EC, F>(
+  source: S, mut next: N, mut err_or_complete: EC, on_empty: F,
+) -> S::Unsubscribe
+where
+  S: Observable<'a>,
+  S::Item: 'a,
+  N: 'a + FnMut(S::Item) -> OState,
+  EC: 'a + FnMut(&ErrComplete<S::Err>),
+  F: 'a + FnOnce() -> Result<S::Item, S::Err>,
+{
+  let last = Rc::new(RefCell::new(None));
+  let last_on_ec = last.clone();
+  let mut on_empty = Some(on_empty);
+  source.subscribe_return_state(
+    move |v| {
+      *last.borrow_mut() = Some(v);
+      OState::Next
+    },
+    move |ec| {
+      if let ErrComplete::Err(_) = ec {
+        return err_or_complete(ec);
+      }
+      let last = last_on_ec.borrow_mut().take().map(Ok);
+      let state = match last.or_else(|| on_empty.take().map(|f| f())) {
+        Some(Ok(v)) => next(v),
+        Some(Err(err)) => return err_or_complete(&ErrComplete::Err(err)),
+        None => return,
+      };
+      if state == OState::Next {
+        err_or_complete(ec);
+      }
+    },
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{empty, from_iter},
+    ops::{EmptyError, Last},
+    testing::TestObserver,
+    Observer, Subject,
+  };
+
+  #[derive(Clone, Debug, PartialEq)]
+  enum Error {
+    Empty,
+    Io,
+  }
+
+  impl From<EmptyError> for Error {
+    fn from(_: EmptyError) -> Self { Error::Empty }
+  }
+
+  #[test]
+  fn emits_last_on_complete() {
+    let subject = Subject::<_, Error>::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(subject.clone().last());
+    subject.next(1).next(2);
+    observer.assert_values(&[]);
+    subject.complete();
+    observer.assert_values(&[2]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn empty_fails_or_emits_default() {
+    let failing = TestObserver::<i32, Error>::new();
+    failing.subscribe_to(empty().last());
+    failing.assert_values(&[]);
+    failing.assert_error(&Error::Empty);
+
+    let defaulted = TestObserver::<_, Error>::new();
+    defaulted.subscribe_to(empty().last_or(7));
+    defaulted.assert_values(&[7]);
+    defaulted.assert_completed();
+
+    let ignored = TestObserver::<_, Error>::new();
+    ignored.subscribe_to(from_iter(0..3).last_or(7));
+    ignored.assert_values(&[2]);
+  }
+
+  #[test]
+  fn error_passes_through() {
+    let subject = Subject::<i32, _>::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(subject.clone().last());
+    subject.next(1);
+    subject.err(Error::Io);
+    observer.assert_values(&[]);
+    observer.assert_error(&Error::Io);
+  }
+}
@@ -1,4 +1,4 @@
-use crate::{ErrComplete, Observable};
+use crate::{ErrComplete, OState, Observable};
 
 /// Creates a new stream which calls a closure on each element and uses
 /// its return as the value.
@@ -32,20 +32,17 @@ where
   type Unsubscribe = S::Unsubscribe;
   type Err = S::Err;
 
-  fn subscribe<N, EC>(
+  fn subscribe_return_state<N, EC>(
     self, mut next: N, err_or_complete: EC,
   ) -> Self::Unsubscribe
   where
-    N: 'a + FnMut(Self::Item),
+    N: 'a + FnMut(Self::Item) -> OState,
     EC: 'a + FnMut(&ErrComplete<Self::Err>),
   {
     let mut func = self.func;
-    self.source.subscribe(
-      move |v| {
-        next(func(v));
-      },
-      err_or_complete,
-    )
+    self
+      .source
+      .subscribe_return_state(move |v| next(func(v)), err_or_complete)
   }
 }
 
@@ -76,8 +73,8 @@ mod test {
       let subject = Subject::new();
       subject
         .clone()
-        .map(|v: &&i32| v)
-        .subscribe(|v| i = **v, |_: &ErrComplete<()>| {});
+        .map(|v: &i32| v)
+        .subscribe(|v| i = *v, |_: &ErrComplete<()>| {});
       subject.next(&100);
     }
     assert_eq!(i, 100);
@@ -90,11 +87,25 @@ mod test {
       let subject = Subject::new();
       subject
         .clone()
-        .map(|v: &&i32| v)
-        .subscribe(|v| i = **v, |_: &ErrComplete<()>| {})
+        .map(|v: &i32| v)
+        .subscribe(|v| i = *v, |_: &ErrComplete<()>| {})
         .unsubscribe();
       subject.next(&100);
     }
     assert_eq!(i, 0);
   }
+
+  #[test]
+  fn late_subscriber_completes() {
+    let mut completed = false;
+    {
+      let subject = Subject::<i32, ()>::new();
+      subject.clone().complete();
+      subject
+        .clone()
+        .map(|v| v * 2)
+        .subscribe(|_| {}, |ec| completed = *ec == ErrComplete::Complete);
+    }
+    assert!(completed);
+  }
 }
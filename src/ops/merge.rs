@@ -1,16 +1,24 @@
-use crate::{ErrComplete, Observable, Subscription};
-use std::cell::RefCell;
+use crate::{
+  ops::{merge_all, MergeAllOp},
+  ErrComplete, OState, Observable, Subscription,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::iter;
 use std::rc::Rc;
 
 /// combine two Observables into one by merging their emissions
 ///
+/// The merged stream completes after both sources have completed, and fails
+/// as soon as either source fails, leaving the other source at once.
+///
 /// # Example
 ///
 /// ```
 /// # use rx_rs::{
 ///   ops::{Filter, Merge}, Observable, Observer, Subject, ErrComplete
 ///  };
-/// let numbers = Subject::new();
+/// let numbers = Subject::<i32, _>::new();
 /// // crate a even stream by filter
 /// let even = numbers.clone().filter(|v| *v % 2 == 0);
 /// // crate an odd stream by filter
@@ -33,6 +41,18 @@ pub trait Merge<'a, T> {
       source2: o,
     }
   }
+
+  /// Merge this observable with all the observables of `others`, see
+  /// [`merge_all`](fn.merge_all.html).
+  fn merge_iter<I>(
+    self, others: I,
+  ) -> MergeAllOp<iter::Chain<iter::Once<Self>, I::IntoIter>>
+  where
+    Self: Observable<'a>,
+    I: IntoIterator<Item = Self>,
+  {
+    merge_all(iter::once(self).chain(others))
+  }
 }
 
 impl<'a, T, O> Merge<'a, T> for O where O: Observable<'a, Item = T> {}
@@ -44,36 +64,55 @@ pub struct MergeOp<S1, S2> {
 
 impl<'a, T, S1, S2, E> Observable<'a> for MergeOp<S1, S2>
 where
+  T: 'a,
   S1: Observable<'a, Item = T, Err = E>,
   S2: Observable<'a, Item = T, Err = E>,
+  S1::Unsubscribe: 'a,
+  S2::Unsubscribe: 'a,
 {
   type Item = T;
   type Unsubscribe = MergeSubscription<S1::Unsubscribe, S2::Unsubscribe>;
   type Err = E;
 
-  fn subscribe<N, EC>(self, next: N, err_or_complete: EC) -> Self::Unsubscribe
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
   where
-    N: 'a + FnMut(Self::Item),
+    N: 'a + FnMut(Self::Item) -> OState,
     EC: 'a + FnMut(&ErrComplete<Self::Err>),
   {
-    let next = Rc::new(RefCell::new(next));
-    let next_clone = next.clone();
-    ;
-    let on_ec1 = Rc::new(RefCell::new(err_or_complete));
-    let on_ec2 = on_ec1.clone();
+    let state = Rc::new(MergeState {
+      next: Forward::new(next),
+      err_or_complete: RefCell::new(err_or_complete),
+      completed: Cell::new(0),
+      stopped: Cell::new(false),
+    });
+    let subscription1 = Rc::new(RefCell::new(None));
+    let subscription2 = Rc::new(RefCell::new(None));
 
-    let subscription1 = self.source1.subscribe(
-      move |v| {
-        (&mut *next.borrow_mut())(v);
-      },
-      move |ec| (&mut *on_ec1.borrow_mut())(ec),
-    );
-    let subscription2 = self.source2.subscribe(
-      move |v| {
-        (&mut *next_clone.borrow_mut())(v);
-      },
-      move |ec| (&mut *on_ec2.borrow_mut())(ec),
+    let next_state = state.clone();
+    let ec_state = state.clone();
+    let other = subscription2.clone();
+    let other_on_ec = subscription2.clone();
+    let s1 = self.source1.subscribe_return_state(
+      move |v| next_state.next(v, &other),
+      move |ec| ec_state.err_or_complete(ec, &other_on_ec),
     );
+    *subscription1.borrow_mut() = Some(s1);
+
+    // the first source may have failed synchronously, then the second one is
+    // never needed.
+    if !state.stopped.get() {
+      let next_state = state.clone();
+      let ec_state = state.clone();
+      let other = subscription1.clone();
+      let other_on_ec = subscription1.clone();
+      let s2 = self.source2.subscribe_return_state(
+        move |v| next_state.next(v, &other),
+        move |ec| ec_state.err_or_complete(ec, &other_on_ec),
+      );
+      *subscription2.borrow_mut() = Some(s2);
+    }
 
     MergeSubscription {
       subscription1,
@@ -82,9 +121,134 @@ where
   }
 }
 
+struct MergeState<T, N, EC> {
+  next: Forward<T, N>,
+  err_or_complete: RefCell<EC>,
+  completed: Cell<usize>,
+  stopped: Cell<bool>,
+}
+
+impl<T, N, EC> MergeState<T, N, EC> {
+  /// Emit `v`, if the observer is done stop both sources: the emitting one
+  /// by the returned state, and the `other` one by unsubscribing it.
+  fn next<E, S>(&self, v: T, other: &RefCell<Option<S>>) -> OState
+  where
+    N: FnMut(T) -> OState,
+    EC: FnMut(&ErrComplete<E>),
+    S: Subscription,
+  {
+    if self.stopped.get() {
+      return OState::Complete;
+    }
+    let state = self.next.forward(v, || self.stopped.get());
+    if self.stopped.get() {
+      // a source failed while the observer was running.
+      return OState::Complete;
+    }
+    if state == OState::Complete {
+      self.stopped.set(true);
+      let other = other.borrow_mut().take();
+      if let Some(other) = other {
+        other.unsubscribe();
+      }
+    } else if self.completed.get() == 2 && !self.next.is_emitting() {
+      // both sources completed while the observer was running.
+      self.stopped.set(true);
+      (*self.err_or_complete.borrow_mut())(&ErrComplete::Complete);
+    }
+    state
+  }
+
+  /// Complete once both sources completed, but fail as soon as one source
+  /// fails, and stop listening to the `other` source then.
+  fn err_or_complete<E, S>(
+    &self, ec: &ErrComplete<E>, other: &RefCell<Option<S>>,
+  ) where
+    EC: FnMut(&ErrComplete<E>),
+    S: Subscription,
+  {
+    if self.stopped.get() {
+      return;
+    }
+    match ec {
+      ErrComplete::Complete => {
+        self.completed.set(self.completed.get() + 1);
+        // while the observer runs, the items it fed back go first.
+        if self.completed.get() < 2 || self.next.is_emitting() {
+          return;
+        }
+      }
+      ErrComplete::Err(_) => {
+        let other = other.borrow_mut().take();
+        if let Some(other) = other {
+          other.unsubscribe();
+        }
+      }
+    }
+    self.stopped.set(true);
+    (*self.err_or_complete.borrow_mut())(ec);
+  }
+}
+
+/// Forwards the items of merged sources to the observer one at a time. An
+/// item arriving while the observer runs, e.g. fed back by the observer
+/// into another merged source, is queued and forwarded once it returns.
+pub(crate) struct Forward<T, N> {
+  next: RefCell<N>,
+  queue: RefCell<VecDeque<T>>,
+  emitting: Cell<bool>,
+}
+
+impl<T, N> Forward<T, N>
+where
+  N: FnMut(T) -> OState,
+{
+  pub(crate) fn new(next: N) -> Self {
+    Forward {
+      next: RefCell::new(next),
+      queue: RefCell::new(VecDeque::new()),
+      emitting: Cell::new(false),
+    }
+  }
+
+  /// Forward `v` and the items queued meanwhile, until the observer is done
+  /// or `stopped` returns true. Return the state of the observer, `Next`
+  /// when `v` is only queued.
+  pub(crate) fn forward<F>(&self, v: T, stopped: F) -> OState
+  where
+    F: Fn() -> bool,
+  {
+    if self.emitting.get() {
+      self.queue.borrow_mut().push_back(v);
+      return OState::Next;
+    }
+    self.emitting.set(true);
+    let mut v = v;
+    let state = loop {
+      let state = (*self.next.borrow_mut())(v);
+      if state == OState::Complete || stopped() {
+        break state;
+      }
+      let queued = self.queue.borrow_mut().pop_front();
+      match queued {
+        Some(queued) => v = queued,
+        None => break state,
+      }
+    };
+    self.emitting.set(false);
+    self.queue.borrow_mut().clear();
+    state
+  }
+}
+
+impl<T, N> Forward<T, N> {
+  /// The observer is running, items are queued.
+  pub(crate) fn is_emitting(&self) -> bool { self.emitting.get() }
+}
+
 pub struct MergeSubscription<S1, S2> {
-  subscription1: S1,
-  subscription2: S2,
+  subscription1: Rc<RefCell<Option<S1>>>,
+  subscription2: Rc<RefCell<Option<S2>>>,
 }
 
 impl<S1, S2> Subscription for MergeSubscription<S1, S2>
@@ -93,15 +257,23 @@ where
   S2: Subscription,
 {
   fn unsubscribe(self) {
-    self.subscription1.unsubscribe();
-    self.subscription2.unsubscribe();
+    let s1 = self.subscription1.borrow_mut().take();
+    if let Some(s) = s1 {
+      s.unsubscribe();
+    }
+    let s2 = self.subscription2.borrow_mut().take();
+    if let Some(s) = s2 {
+      s.unsubscribe();
+    }
   }
 }
 
 #[cfg(test)]
 mod test {
   use crate::{
-    ops::{Filter, Merge},
+    creation::from_iter,
+    ops::{Filter, Map, Merge, Take},
+    testing::TestObserver,
     ErrComplete, Observable, Observer, Subject, Subscription,
   };
   use std::cell::RefCell;
@@ -125,19 +297,19 @@ mod test {
 
     //  attach observers
     merged.subscribe(
-      |v| numbers_store.borrow_mut().push(**v),
+      |v| numbers_store.borrow_mut().push(v),
       |_ec: &ErrComplete<()>| {},
     );
     odd.subscribe(
-      |v| odd_store.borrow_mut().push(**v),
+      |v| odd_store.borrow_mut().push(v),
       |_ec: &ErrComplete<()>| {},
     );
     even.subscribe(
-      |v| even_store.borrow_mut().push(**v),
+      |v| even_store.borrow_mut().push(v),
       |_ec: &ErrComplete<()>| {},
     );
 
-    (0..10).into_iter().for_each(|v| {
+    (0..10).for_each(|v| {
       numbers.next(v);
     });
 
@@ -165,4 +337,117 @@ mod test {
     numbers.next(1);
   }
 
+  #[test]
+  fn observer_feeds_other_source() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let completed = Rc::new(RefCell::new(0));
+    let a = Subject::new();
+    let b = Subject::new();
+
+    let r = received.clone();
+    let c = completed.clone();
+    let feedback = b.clone();
+    a.clone().merge(b.clone()).subscribe(
+      move |v| {
+        r.borrow_mut().push(v);
+        if v == 1 {
+          feedback.next(2).next(3);
+          feedback.clone().complete();
+        }
+      },
+      move |_: &ErrComplete<()>| *c.borrow_mut() += 1,
+    );
+
+    a.next(1);
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+    assert_eq!(*completed.borrow(), 0);
+    a.complete();
+    assert_eq!(*completed.borrow(), 1);
+  }
+
+  #[test]
+  fn completed_after_both_sources_completed() {
+    let completed = Rc::new(RefCell::new(0));
+    let even = Subject::new();
+    let odd = Subject::new();
+
+    let c = completed.clone();
+    even.clone().merge(odd.clone()).subscribe(
+      |_: i32| {},
+      move |ec: &ErrComplete<()>| {
+        assert_eq!(ec, &ErrComplete::Complete);
+        *c.borrow_mut() += 1;
+      },
+    );
+
+    even.complete();
+    assert_eq!(*completed.borrow(), 0);
+    odd.complete();
+    assert_eq!(*completed.borrow(), 1);
+  }
+
+  #[test]
+  fn error_stops_merge() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let errors = Rc::new(RefCell::new(vec![]));
+    let numbers = Subject::new();
+    let failing = Subject::new();
+
+    let r = received.clone();
+    let e = errors.clone();
+    numbers.clone().merge(failing.clone()).subscribe(
+      move |v| r.borrow_mut().push(v),
+      move |ec: &ErrComplete<&str>| e.borrow_mut().push(ec.clone()),
+    );
+
+    numbers.next(1);
+    failing.next(2);
+    failing.clone().err("boom");
+    numbers.next(3);
+    numbers.complete();
+
+    assert_eq!(*received.borrow(), vec![1, 2]);
+    assert_eq!(*errors.borrow(), vec![ErrComplete::Err("boom")]);
+  }
+
+  #[test]
+  fn error_unsubscribes_other_source() {
+    let numbers = Subject::new();
+    let failing = Subject::new();
+    let observed = Rc::new(RefCell::new(0));
+    // observe the merge through `map`, to count what leaves `numbers`.
+    let o = observed.clone();
+    numbers
+      .clone()
+      .map(move |v: i32| {
+        *o.borrow_mut() += 1;
+        v
+      })
+      .merge(failing.clone())
+      .subscribe(|_| {}, |_: &ErrComplete<()>| {});
+
+    numbers.next(1);
+    failing.err(());
+    numbers.next(2);
+    assert_eq!(*observed.borrow(), 1);
+  }
+
+  #[test]
+  fn observer_done_stops_both_sources() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..).merge(from_iter(100..)).take(3));
+    observer.assert_values(&[0, 1, 2]);
+    observer.assert_completed();
+
+    let a = Subject::<i32, ()>::new();
+    let b = Subject::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(a.clone().merge(b.clone()).take(2));
+    a.next(1);
+    b.next(2);
+    a.next(3);
+    b.next(4);
+    observer.assert_values(&[1, 2]);
+    observer.assert_completed();
+  }
 }
@@ -0,0 +1,545 @@
+use crate::{
+  ops::merge::Forward, ErrComplete, OState, Observable, Subscription,
+};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Merge all the observables of an iterator into one observable, emitting
+/// the emissions of all of them.
+///
+/// The merged stream completes after every source has completed, and fails
+/// as soon as one source fails, unsubscribing from all the others.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{ops::merge_all, ErrComplete, Observable, Observer, Subject};
+/// let sources = (0..10).map(|_| Subject::new()).collect::<Vec<_>>();
+///
+/// merge_all(sources.clone()).subscribe(
+///   |v| println!("{}", v),
+///   |ec: &ErrComplete<()>| println!("all sources completed"),
+/// );
+///
+/// sources.iter().enumerate().for_each(|(i, s)| {
+///   s.next(i);
+/// });
+/// sources.into_iter().for_each(|s| s.complete());
+/// ```
+///
+/// Sources of different types can be merged after boxing them by
+/// [`BoxIt::box_it`](trait.BoxIt.html).
+pub fn merge_all<'a, I>(sources: I) -> MergeAllOp<I::IntoIter>
+where
+  I: IntoIterator,
+  I::Item: Observable<'a>,
+{
+  MergeAllOp {
+    sources: sources.into_iter(),
+    max_concurrent: usize::MAX,
+  }
+}
+
+pub struct MergeAllOp<I> {
+  sources: I,
+  max_concurrent: usize,
+}
+
+impl<I> MergeAllOp<I> {
+  /// Subscribe at most `limit` sources at the same time, the other ones are
+  /// subscribed in order as the active ones complete.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `limit` is 0.
+  pub fn max_concurrent(mut self, limit: usize) -> Self {
+    assert!(limit > 0, "merge at least one source at a time");
+    self.max_concurrent = limit;
+    self
+  }
+}
+
+impl<'a, I, O> Observable<'a> for MergeAllOp<I>
+where
+  I: Iterator<Item = O>,
+  O: Observable<'a> + 'a,
+  O::Item: 'a,
+  O::Unsubscribe: 'a,
+{
+  type Item = O::Item;
+  type Err = O::Err;
+  type Unsubscribe = MergeAllSubscription<O, O::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let state = MergeAllState::new(
+      self.max_concurrent,
+      |source| source,
+      next,
+      err_or_complete,
+    );
+    for source in self.sources {
+      if state.is_stopped() {
+        break;
+      }
+      state.add_source(source);
+    }
+    state.no_more_sources();
+    state.subscription()
+  }
+}
+
+/// Book-keeping of the merged sources, shared with the subscription.
+pub(crate) struct MergeAllSources<P, S> {
+  max_concurrent: usize,
+  /// Sources waiting for a free slot, not created yet.
+  pending: VecDeque<P>,
+  /// Subscribed sources, keyed by an increasing id. The subscription is
+  /// `None` until the source's `subscribe` returns.
+  active: Vec<(usize, Option<S>)>,
+  next_id: usize,
+  /// All the sources to merge have been added.
+  no_more_sources: bool,
+  /// Pending sources are being subscribed, a source completing meanwhile
+  /// only frees its slot for the running loop, rather than subscribing the
+  /// next one itself, which would nest a call per synchronous source.
+  draining: bool,
+  stopped: bool,
+}
+
+impl<P, S: Subscription> MergeAllSources<P, S> {
+  fn remove_active(&mut self, id: usize) -> Option<Option<S>> {
+    self
+      .active
+      .binary_search_by_key(&id, |(id, _)| *id)
+      .ok()
+      .map(|idx| self.active.remove(idx).1)
+  }
+
+  fn is_finished(&self) -> bool {
+    self.no_more_sources && self.active.is_empty() && self.pending.is_empty()
+  }
+
+  /// Stop merging, return the subscriptions to release.
+  fn stop(&mut self) -> Vec<S> {
+    self.stopped = true;
+    self.pending.clear();
+    self.active.drain(..).filter_map(|(_, s)| s).collect()
+  }
+}
+
+/// The merging engine, sources are added one by one, so operators merging
+/// observables they create on the fly can share it.
+///
+/// What is added is a `P` that `project` turns into the source, only once
+/// the source gets a slot, so the sources waiting for a slot are not even
+/// created.
+pub(crate) struct MergeAllState<P, T, S, M, N, EC> {
+  sources: Rc<RefCell<MergeAllSources<P, S>>>,
+  project: RefCell<M>,
+  next: Forward<T, N>,
+  err_or_complete: RefCell<EC>,
+}
+
+impl<P, T, S, M, N, EC> MergeAllState<P, T, S, M, N, EC>
+where
+  S: Subscription,
+  N: FnMut(T) -> OState,
+{
+  pub(crate) fn new(
+    max_concurrent: usize, project: M, next: N, err_or_complete: EC,
+  ) -> Rc<Self> {
+    Rc::new(MergeAllState {
+      sources: Rc::new(RefCell::new(MergeAllSources {
+        max_concurrent,
+        pending: VecDeque::new(),
+        active: vec![],
+        next_id: 0,
+        no_more_sources: false,
+        draining: false,
+        stopped: false,
+      })),
+      project: RefCell::new(project),
+      next: Forward::new(next),
+      err_or_complete: RefCell::new(err_or_complete),
+    })
+  }
+
+  /// The merged stream has completed, failed, or been unsubscribed.
+  pub(crate) fn is_stopped(&self) -> bool { self.sources.borrow().stopped }
+
+  /// A subscription unsubscribing all the sources.
+  pub(crate) fn subscription(&self) -> MergeAllSubscription<P, S> {
+    MergeAllSubscription {
+      sources: self.sources.clone(),
+    }
+  }
+}
+
+impl<'a, P, O, M, N, EC> MergeAllState<P, O::Item, O::Unsubscribe, M, N, EC>
+where
+  P: 'a,
+  O: Observable<'a>,
+  O::Item: 'a,
+  O::Unsubscribe: 'a,
+  M: 'a + FnMut(P) -> O,
+  N: 'a + FnMut(O::Item) -> OState,
+  EC: 'a + FnMut(&ErrComplete<O::Err>),
+{
+  /// Subscribe the source made of `source` now if there is a free slot,
+  /// otherwise queue it.
+  pub(crate) fn add_source(self: &Rc<Self>, source: P) {
+    {
+      let mut sources = self.sources.borrow_mut();
+      if sources.stopped {
+        return;
+      }
+      sources.pending.push_back(source);
+    }
+    self.drain();
+  }
+
+  /// Subscribe the pending sources while there are free slots, then
+  /// complete if there is nothing left to merge.
+  fn drain(self: &Rc<Self>) {
+    {
+      let mut sources = self.sources.borrow_mut();
+      if sources.draining {
+        return;
+      }
+      sources.draining = true;
+    }
+    loop {
+      let source = {
+        let mut sources = self.sources.borrow_mut();
+        if sources.stopped || sources.active.len() >= sources.max_concurrent {
+          None
+        } else {
+          sources.pending.pop_front()
+        }
+      };
+      match source {
+        Some(source) => self.subscribe_source(source),
+        None => break,
+      }
+    }
+    self.sources.borrow_mut().draining = false;
+    self.complete_if_finished();
+  }
+
+  /// No source will be added anymore, the merged stream can complete once
+  /// the added ones have completed.
+  pub(crate) fn no_more_sources(&self) {
+    self.sources.borrow_mut().no_more_sources = true;
+    self.complete_if_finished();
+  }
+
+  /// Complete once every source has completed, and the items fed back by
+  /// the observer meanwhile have been forwarded.
+  fn complete_if_finished(&self) {
+    {
+      let mut sources = self.sources.borrow_mut();
+      let busy = sources.draining || self.next.is_emitting();
+      if sources.stopped || !sources.is_finished() || busy {
+        return;
+      }
+      sources.stopped = true;
+    }
+    (*self.err_or_complete.borrow_mut())(&ErrComplete::Complete);
+  }
+
+  /// Fail the merged stream and unsubscribe all the sources.
+  pub(crate) fn error(&self, ec: &ErrComplete<O::Err>) {
+    if self.stop() {
+      (*self.err_or_complete.borrow_mut())(ec);
+    }
+  }
+
+  /// Unsubscribe all the sources, return false if already stopped.
+  fn stop(&self) -> bool {
+    let subscriptions = {
+      let mut sources = self.sources.borrow_mut();
+      if sources.stopped {
+        return false;
+      }
+      sources.stop()
+    };
+    subscriptions.into_iter().for_each(|s| s.unsubscribe());
+    true
+  }
+
+  fn subscribe_source(self: &Rc<Self>, source: P) {
+    let source = (*self.project.borrow_mut())(source);
+    let id = {
+      let mut sources = self.sources.borrow_mut();
+      let id = sources.next_id;
+      sources.next_id += 1;
+      sources.active.push((id, None));
+      id
+    };
+
+    let for_next = self.clone();
+    let for_ec = self.clone();
+    let subscription = source.subscribe_return_state(
+      move |v| {
+        if for_next.is_stopped() {
+          return OState::Complete;
+        }
+        let state = for_next.next.forward(v, || for_next.is_stopped());
+        if for_next.is_stopped() {
+          // a source failed while the observer was running.
+          return OState::Complete;
+        }
+        if state == OState::Complete {
+          // the emitting source stops by the returned state.
+          for_next.sources.borrow_mut().remove_active(id);
+          for_next.stop();
+        } else {
+          for_next.complete_if_finished();
+        }
+        state
+      },
+      move |ec| for_ec.source_err_or_complete(id, ec),
+    );
+
+    let mut sources = self.sources.borrow_mut();
+    match sources.active.binary_search_by_key(&id, |(id, _)| *id) {
+      Ok(idx) => sources.active[idx].1 = Some(subscription),
+      // the source has already completed, or the merge has stopped.
+      Err(_) => {
+        drop(sources);
+        subscription.unsubscribe();
+      }
+    }
+  }
+
+  fn source_err_or_complete(
+    self: &Rc<Self>, id: usize, ec: &ErrComplete<O::Err>,
+  ) {
+    match ec {
+      ErrComplete::Complete => {
+        let mut sources = self.sources.borrow_mut();
+        if sources.stopped {
+          return;
+        }
+        sources.remove_active(id);
+        drop(sources);
+        self.drain();
+      }
+      ErrComplete::Err(_) => {
+        // the failed source has finished, it needn't be unsubscribed.
+        self.sources.borrow_mut().remove_active(id);
+        self.error(ec);
+      }
+    }
+  }
+}
+
+pub struct MergeAllSubscription<P, S> {
+  sources: Rc<RefCell<MergeAllSources<P, S>>>,
+}
+
+impl<P, S: Subscription> Subscription for MergeAllSubscription<P, S> {
+  fn unsubscribe(self) {
+    let subscriptions = self.sources.borrow_mut().stop();
+    subscriptions.into_iter().for_each(|s| s.unsubscribe());
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::of,
+    ops::{merge_all, BoxIt, Filter, Map, Merge},
+    ErrComplete, Observable, Observer, Subject, Subscription,
+  };
+  use std::cell::RefCell;
+  use std::iter;
+  use std::rc::Rc;
+
+  #[test]
+  fn merge_many_subjects() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let completed = Rc::new(RefCell::new(0));
+    let sources = (0..10).map(|_| Subject::new()).collect::<Vec<_>>();
+
+    let r = received.clone();
+    let c = completed.clone();
+    merge_all(sources.clone()).subscribe(
+      move |v| r.borrow_mut().push(v),
+      move |_: &ErrComplete<()>| *c.borrow_mut() += 1,
+    );
+
+    sources.iter().enumerate().rev().for_each(|(i, s)| {
+      s.next(i);
+    });
+    assert_eq!(*received.borrow(), (0..10).rev().collect::<Vec<_>>());
+
+    let (last, others) = sources.split_last().unwrap();
+    others.iter().for_each(|s| s.clone().complete());
+    assert_eq!(*completed.borrow(), 0);
+    last.clone().complete();
+    assert_eq!(*completed.borrow(), 1);
+  }
+
+  #[test]
+  fn observer_feeds_other_source() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let sources = (0..2).map(|_| Subject::new()).collect::<Vec<_>>();
+
+    let r = received.clone();
+    let feedback = sources[1].clone();
+    merge_all(sources.clone()).subscribe(
+      move |v| {
+        r.borrow_mut().push(v);
+        if v == 0 {
+          feedback.next(1);
+        }
+      },
+      |_: &ErrComplete<()>| {},
+    );
+
+    sources[0].next(0);
+    assert_eq!(*received.borrow(), vec![0, 1]);
+  }
+
+  #[test]
+  fn many_queued_synchronous_sources() {
+    let received = Rc::new(RefCell::new(0));
+    let completed = Rc::new(RefCell::new(false));
+    let first = Subject::new();
+    let queued = (0..100_000).map(|i| of(i).box_it());
+
+    let r = received.clone();
+    let c = completed.clone();
+    merge_all(iter::once(first.clone().box_it()).chain(queued))
+      .max_concurrent(1)
+      .subscribe(
+        move |v| {
+          assert_eq!(v, *r.borrow());
+          *r.borrow_mut() += 1;
+        },
+        move |_: &ErrComplete<()>| *c.borrow_mut() = true,
+      );
+
+    first.complete();
+    assert_eq!(*received.borrow(), 100_000);
+    assert!(*completed.borrow());
+  }
+
+  #[test]
+  fn merge_iter_same_type() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let a = Subject::<i32, ()>::new();
+    let b = Subject::new();
+    let c = Subject::new();
+
+    let r = received.clone();
+    a.clone()
+      .merge_iter(vec![b.clone(), c.clone()])
+      .subscribe(move |v| r.borrow_mut().push(v), |_| {});
+    c.next(3);
+    a.next(1);
+    b.next(2);
+    assert_eq!(*received.borrow(), vec![3, 1, 2]);
+  }
+
+  #[test]
+  fn merge_boxed() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let numbers = Subject::<i32, ()>::new();
+    let r = received.clone();
+    merge_all(vec![
+      numbers.clone().filter(|v| *v % 2 == 0).box_it(),
+      numbers.clone().map(|v| v * 100).box_it(),
+    ])
+    .subscribe(move |v| r.borrow_mut().push(v), |_| {});
+
+    numbers.next(1);
+    numbers.next(2);
+    assert_eq!(*received.borrow(), vec![100, 2, 200]);
+  }
+
+  #[test]
+  fn error_unsubscribes_all_sources() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let errors = Rc::new(RefCell::new(vec![]));
+    let sources = (0..5).map(|_| Subject::new()).collect::<Vec<_>>();
+
+    let r = received.clone();
+    let e = errors.clone();
+    merge_all(sources.clone()).subscribe(
+      move |v| r.borrow_mut().push(v),
+      move |ec: &ErrComplete<&str>| e.borrow_mut().push(ec.clone()),
+    );
+
+    sources[2].clone().err("boom");
+    sources.iter().for_each(|s| {
+      s.next(1);
+    });
+    sources[0].clone().err("again");
+    sources.into_iter().for_each(|s| s.complete());
+
+    assert!(received.borrow().is_empty());
+    assert_eq!(*errors.borrow(), vec![ErrComplete::Err("boom")]);
+  }
+
+  #[test]
+  fn max_concurrent() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let completed = Rc::new(RefCell::new(false));
+    let sources = (0..4).map(|_| Subject::new()).collect::<Vec<_>>();
+
+    let r = received.clone();
+    let c = completed.clone();
+    merge_all(sources.clone()).max_concurrent(2).subscribe(
+      move |v| r.borrow_mut().push(v),
+      move |_: &ErrComplete<()>| *c.borrow_mut() = true,
+    );
+
+    let emit_all = |round: usize| {
+      sources.iter().enumerate().for_each(|(i, s)| {
+        s.next(round * 10 + i);
+      });
+    };
+    emit_all(0);
+    assert_eq!(*received.borrow(), vec![0, 1]);
+
+    // completing the first source makes room for the third one.
+    sources[0].clone().complete();
+    emit_all(1);
+    assert_eq!(*received.borrow(), vec![0, 1, 11, 12]);
+
+    sources[1].clone().complete();
+    sources[2].clone().complete();
+    emit_all(2);
+    assert_eq!(*received.borrow(), vec![0, 1, 11, 12, 23]);
+    assert!(!*completed.borrow());
+
+    sources[3].clone().complete();
+    assert!(*completed.borrow());
+  }
+
+  #[test]
+  fn unsubscribe_all() {
+    let sources = (0..5).map(|_| Subject::new()).collect::<Vec<_>>();
+    merge_all(sources.clone())
+      .max_concurrent(3)
+      .subscribe(
+        |_| unreachable!("all sources unsubscribed"),
+        |_: &ErrComplete<()>| unreachable!("all sources unsubscribed"),
+      )
+      .unsubscribe();
+
+    sources.iter().for_each(|s| {
+      s.next(1);
+    });
+    sources.into_iter().for_each(|s| s.complete());
+  }
+}
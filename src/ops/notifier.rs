@@ -0,0 +1,151 @@
+use crate::{ErrComplete, OState, Observable, Subscription};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// What a notification of the notifier does to the stream.
+pub(crate) enum Notified {
+  /// Keep listening to the notifier.
+  Listen,
+  /// Unsubscribe the notifier, the source goes on.
+  Unsubscribe,
+  /// Complete the stream, unsubscribing the source.
+  Complete,
+}
+
+/// Subscribes `source` alongside `notifier`, for operators driven by
+/// another observable.
+///
+/// `on_notify` runs on every item of the notifier, and the items of the
+/// source for which `pass` returns false are dropped. An error of either
+/// observable fails the stream, the completion of the notifier is ignored.
+///
+/// The notifier is subscribed first, so a synchronous notifier completing
+/// the stream prevents subscribing the source.
+pub(crate) fn subscribe_with_notifier<'a, S, Notify, P, OnNotify, N, EC>(
+  source: S, notifier: Notify, mut pass: P, mut on_notify: OnNotify, next: N,
+  err_or_complete: EC,
+) -> NotifierSubscription<S::Unsubscribe, Notify::Unsubscribe>
+where
+  S: Observable<'a>,
+  Notify: Observable<'a, Err = S::Err>,
+  S::Unsubscribe: 'a,
+  Notify::Unsubscribe: 'a,
+  P: 'a + FnMut(&S::Item) -> bool,
+  OnNotify: 'a + FnMut() -> Notified,
+  N: 'a + FnMut(S::Item) -> OState,
+  EC: 'a + FnMut(&ErrComplete<S::Err>),
+{
+  let state = Rc::new(NotifierState {
+    next: RefCell::new(next),
+    err_or_complete: RefCell::new(err_or_complete),
+    stopped: Cell::new(false),
+  });
+  let source_slot = Rc::new(RefCell::new(None));
+  let notifier_slot = Rc::new(RefCell::new(None));
+
+  let on_notify_state = state.clone();
+  let on_notifier_ec = state.clone();
+  let source_on_notify = source_slot.clone();
+  let source_on_notifier_ec = source_slot.clone();
+  let n = notifier.subscribe_return_state(
+    move |_| match on_notify() {
+      Notified::Listen => OState::Next,
+      Notified::Unsubscribe => OState::Complete,
+      Notified::Complete => {
+        on_notify_state.stop(&ErrComplete::Complete, &source_on_notify);
+        OState::Complete
+      }
+    },
+    move |ec| {
+      if let ErrComplete::Err(_) = ec {
+        on_notifier_ec.stop(ec, &source_on_notifier_ec);
+      }
+    },
+  );
+  *notifier_slot.borrow_mut() = Some(n);
+
+  if !state.stopped.get() {
+    let on_next = state.clone();
+    let on_ec = state.clone();
+    let notifier_on_next = notifier_slot.clone();
+    let notifier_on_ec = notifier_slot.clone();
+    let s = source.subscribe_return_state(
+      move |v| {
+        if on_next.stopped.get() {
+          OState::Complete
+        } else if pass(&v) {
+          on_next.next(v, &notifier_on_next)
+        } else {
+          OState::Next
+        }
+      },
+      move |ec| on_ec.stop(ec, &notifier_on_ec),
+    );
+    *source_slot.borrow_mut() = Some(s);
+  }
+
+  NotifierSubscription {
+    source: source_slot,
+    notifier: notifier_slot,
+  }
+}
+
+struct NotifierState<N, EC> {
+  next: RefCell<N>,
+  err_or_complete: RefCell<EC>,
+  stopped: Cell<bool>,
+}
+
+impl<N, EC> NotifierState<N, EC> {
+  fn next<T, S>(&self, v: T, notifier: &RefCell<Option<S>>) -> OState
+  where
+    N: FnMut(T) -> OState,
+    S: Subscription,
+  {
+    let state = (*self.next.borrow_mut())(v);
+    if state == OState::Complete {
+      self.stopped.set(true);
+      if let Some(notifier) = notifier.borrow_mut().take() {
+        notifier.unsubscribe();
+      }
+    }
+    state
+  }
+
+  /// Notify `ec` and unsubscribe the `other` observable, the one that didn't
+  /// end the stream.
+  fn stop<E, S>(&self, ec: &ErrComplete<E>, other: &RefCell<Option<S>>)
+  where
+    EC: FnMut(&ErrComplete<E>),
+    S: Subscription,
+  {
+    if self.stopped.replace(true) {
+      return;
+    }
+    if let Some(other) = other.borrow_mut().take() {
+      other.unsubscribe();
+    }
+    (*self.err_or_complete.borrow_mut())(ec);
+  }
+}
+
+/// Owns the subscriptions of a source and of the notifier driving it.
+pub struct NotifierSubscription<S, N> {
+  source: Rc<RefCell<Option<S>>>,
+  notifier: Rc<RefCell<Option<N>>>,
+}
+
+impl<S, N> Subscription for NotifierSubscription<S, N>
+where
+  S: Subscription,
+  N: Subscription,
+{
+  fn unsubscribe(self) {
+    if let Some(s) = self.source.borrow_mut().take() {
+      s.unsubscribe();
+    }
+    if let Some(n) = self.notifier.borrow_mut().take() {
+      n.unsubscribe();
+    }
+  }
+}
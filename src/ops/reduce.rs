@@ -0,0 +1,121 @@
+use crate::{ErrComplete, OState, Observable};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Applies an accumulator function to each item, starting from `seed`, and
+/// emits only the final accumulation when the source completes. A source
+/// completing without items emits `seed`, an error is passed through
+/// without any item.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::Reduce, Observable};
+/// let mut sums = vec![];
+/// from_iter::<_, ()>(1..5)
+///   .reduce(0, |sum, v| sum + v)
+///   .subscribe_next(|v| sums.push(v));
+/// assert_eq!(sums, vec![10]);
+/// ```
+pub trait Reduce<'a, T> {
+  fn reduce<B, F>(self, seed: B, f: F) -> ReduceOp<Self, B, F>
+  where
+    Self: Sized,
+    F: FnMut(B, T) -> B + 'a,
+  {
+    ReduceOp {
+      source: self,
+      seed,
+      func: f,
+    }
+  }
+}
+
+impl<'a, T, O> Reduce<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct ReduceOp<S, B, F> {
+  source: S,
+  seed: B,
+  func: F,
+}
+
+impl<'a, B, S, F> Observable<'a> for ReduceOp<S, B, F>
+where
+  S: Observable<'a>,
+  B: 'a,
+  F: FnMut(B, S::Item) -> B + 'a,
+{
+  type Item = B;
+  type Unsubscribe = S::Unsubscribe;
+  type Err = S::Err;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, mut err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut func = self.func;
+    let acc = Rc::new(RefCell::new(Some(self.seed)));
+    let acc_on_ec = acc.clone();
+    self.source.subscribe(
+      move |v| {
+        let mut acc = acc.borrow_mut();
+        *acc = acc.take().map(|prev| func(prev, v));
+      },
+      move |ec| {
+        let acc = acc_on_ec.borrow_mut().take();
+        if let (ErrComplete::Complete, Some(acc)) = (ec, acc) {
+          if next(acc) == OState::Complete {
+            return;
+          }
+        }
+        err_or_complete(ec);
+      },
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::empty, ops::Reduce, testing::TestObserver, Observer, Subject,
+  };
+
+  #[test]
+  fn emits_final_value_on_complete() {
+    let subject = Subject::<_, ()>::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(subject.clone().reduce(vec![], |mut acc, v| {
+      acc.push(v);
+      acc
+    }));
+
+    subject.next(1).next(2);
+    observer.assert_values(&[]);
+    subject.complete();
+    observer.assert_values(&[vec![1, 2]]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn empty_emits_seed() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(empty().reduce(7, |acc, v: i32| acc + v));
+    observer.assert_values(&[7]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn error_passes_through() {
+    let subject = Subject::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(subject.clone().reduce(0, |acc, v| acc + v));
+
+    subject.next(1);
+    subject.err("boom");
+    observer.assert_values(&[]);
+    observer.assert_error(&"boom");
+  }
+}
@@ -0,0 +1,103 @@
+use crate::{ErrComplete, OState, Observable};
+
+/// Applies an accumulator function to each item, starting from `seed`, and
+/// emits every intermediate accumulation.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::Scan, Observable};
+/// let mut totals = vec![];
+/// from_iter::<_, ()>(1..5)
+///   .scan(0, |total, v| total + v)
+///   .subscribe_next(|v| totals.push(v));
+/// assert_eq!(totals, vec![1, 3, 6, 10]);
+/// ```
+pub trait Scan<'a, T> {
+  fn scan<B, F>(self, seed: B, f: F) -> ScanOp<Self, B, F>
+  where
+    Self: Sized,
+    F: FnMut(B, T) -> B + 'a,
+  {
+    ScanOp {
+      source: self,
+      seed,
+      func: f,
+    }
+  }
+}
+
+impl<'a, T, O> Scan<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct ScanOp<S, B, F> {
+  source: S,
+  seed: B,
+  func: F,
+}
+
+impl<'a, B, S, F> Observable<'a> for ScanOp<S, B, F>
+where
+  S: Observable<'a>,
+  B: Clone + 'a,
+  F: FnMut(B, S::Item) -> B + 'a,
+{
+  type Item = B;
+  type Unsubscribe = S::Unsubscribe;
+  type Err = S::Err;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut func = self.func;
+    let mut acc = self.seed;
+    self.source.subscribe_return_state(
+      move |v| {
+        acc = func(acc.clone(), v);
+        next(acc.clone())
+      },
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{ops::Scan, testing::TestObserver, Observer, Subject};
+
+  #[test]
+  fn emits_every_accumulation() {
+    let subject = Subject::<_, ()>::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(
+      subject
+        .clone()
+        .scan(String::new(), |acc, v| format!("{}{}", acc, v)),
+    );
+
+    subject.next('a').next('b');
+    observer.assert_values(&["a".to_owned(), "ab".to_owned()]);
+    subject.complete();
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn each_subscription_starts_from_seed() {
+    let subject = Subject::<i32, &str>::new();
+    let first = TestObserver::new();
+    first.subscribe_to(subject.clone().scan(0, |acc, v| acc + v));
+    subject.next(1).next(2);
+
+    let second = TestObserver::new();
+    second.subscribe_to(subject.clone().scan(0, |acc, v| acc + v));
+    subject.next(3);
+    subject.err("boom");
+
+    first.assert_values(&[1, 3, 6]);
+    second.assert_values(&[3]);
+    second.assert_error(&"boom");
+  }
+}
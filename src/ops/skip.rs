@@ -0,0 +1,88 @@
+use crate::{ErrComplete, OState, Observable};
+
+/// Ignores the first `count` items of the source, then emits the rest.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::Skip, Observable};
+/// let mut received = vec![];
+/// from_iter::<_, ()>(0..5)
+///   .skip(3)
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![3, 4]);
+/// ```
+pub trait Skip<'a> {
+  fn skip(self, count: usize) -> SkipOp<Self>
+  where
+    Self: Sized,
+  {
+    SkipOp {
+      source: self,
+      count,
+    }
+  }
+}
+
+impl<'a, O> Skip<'a> for O where O: Observable<'a> {}
+
+pub struct SkipOp<S> {
+  source: S,
+  count: usize,
+}
+
+impl<'a, S> Observable<'a> for SkipOp<S>
+where
+  S: Observable<'a>,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut remaining = self.count;
+    self.source.subscribe_return_state(
+      move |v| {
+        if remaining == 0 {
+          next(v)
+        } else {
+          remaining -= 1;
+          OState::Next
+        }
+      },
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::from_iter, ops::Skip, testing::TestObserver, Observer, Subject,
+  };
+
+  #[test]
+  fn skips_first_items() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..5).skip(2));
+    observer.assert_values(&[2, 3, 4]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn error_while_skipping() {
+    let subject = Subject::<i32, _>::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(subject.clone().skip(2));
+    subject.next(1);
+    subject.err("boom");
+    observer.assert_values(&[]);
+    observer.assert_error(&"boom");
+  }
+}
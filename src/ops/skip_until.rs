@@ -0,0 +1,129 @@
+use crate::{
+  ops::{
+    notifier::{subscribe_with_notifier, Notified},
+    NotifierSubscription,
+  },
+  ErrComplete, OState, Observable,
+};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Ignores the items of the source until `notifier` emits its first item,
+/// then emits the rest, the notifier is unsubscribed then.
+///
+/// An error of the notifier fails the stream. The completion and the error
+/// of the source pass through whether the notifier emitted or not.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{ops::SkipUntil, ErrComplete, Observable, Observer, Subject};
+/// # use std::cell::RefCell;
+/// let received = RefCell::new(vec![]);
+/// {
+///   let keys = Subject::new();
+///   let ready = Subject::<(), ()>::new();
+///   keys
+///     .clone()
+///     .skip_until(ready.clone())
+///     .subscribe(|v| received.borrow_mut().push(v), |_| {});
+///
+///   keys.next('a');
+///   ready.next(());
+///   keys.next('b');
+/// }
+/// assert_eq!(received.into_inner(), vec!['b']);
+/// ```
+pub trait SkipUntil<'a> {
+  fn skip_until<N>(self, notifier: N) -> SkipUntilOp<Self, N>
+  where
+    Self: Sized,
+  {
+    SkipUntilOp {
+      source: self,
+      notifier,
+    }
+  }
+}
+
+impl<'a, O> SkipUntil<'a> for O where O: Observable<'a> {}
+
+pub struct SkipUntilOp<S, N> {
+  source: S,
+  notifier: N,
+}
+
+impl<'a, S, Notify> Observable<'a> for SkipUntilOp<S, Notify>
+where
+  S: Observable<'a>,
+  Notify: Observable<'a, Err = S::Err>,
+  S::Unsubscribe: 'a,
+  Notify::Unsubscribe: 'a,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = NotifierSubscription<S::Unsubscribe, Notify::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    // the notifier emitted, items pass from now on.
+    let open = Rc::new(Cell::new(false));
+    let on_notify = open.clone();
+    subscribe_with_notifier(
+      self.source,
+      self.notifier,
+      move |_| open.get(),
+      move || {
+        on_notify.set(true);
+        Notified::Unsubscribe
+      },
+      next,
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    ops::SkipUntil,
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot},
+  };
+
+  #[test]
+  fn emits_after_notified() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3), ('x', 0)];
+    let source = hot::<_, ()>(&scheduler, "-a-b-c-|", &values);
+    let notifier = cold(&scheduler, "--x", &values);
+    expect_observable(&scheduler, source.skip_until(notifier))
+      .to_be("---b-c-|", &values);
+  }
+
+  #[test]
+  fn notifier_never_emitting() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1)];
+    let source = hot::<_, ()>(&scheduler, "-a-a|", &values);
+    let notifier = cold(&scheduler, "-|", &values);
+    expect_observable(&scheduler, source.skip_until(notifier))
+      .to_be("----|", &values);
+  }
+
+  #[test]
+  fn notifier_error_fails() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1)];
+    let source = hot(&scheduler, "-a-a|", &values);
+    let notifier = cold(&scheduler, "--#", &values).with_error("boom");
+    expect_observable(&scheduler, source.skip_until(notifier))
+      .with_error("boom")
+      .to_be("--#", &values);
+  }
+}
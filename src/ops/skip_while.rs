@@ -0,0 +1,80 @@
+use crate::{ErrComplete, OState, Observable};
+
+/// Ignores the items of the source while they pass a predicate test, then
+/// emits every item from the first one failing it on, the predicate isn't
+/// called anymore.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::SkipWhile, Observable};
+/// let mut received = vec![];
+/// from_iter::<_, ()>(vec![1, 3, 4, 5])
+///   .skip_while(|v| v % 2 == 1)
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![4, 5]);
+/// ```
+pub trait SkipWhile<'a, T> {
+  fn skip_while<F>(self, predicate: F) -> SkipWhileOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(&T) -> bool + 'a,
+  {
+    SkipWhileOp {
+      source: self,
+      predicate,
+    }
+  }
+}
+
+impl<'a, T, O> SkipWhile<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct SkipWhileOp<S, F> {
+  source: S,
+  predicate: F,
+}
+
+impl<'a, S, F> Observable<'a> for SkipWhileOp<S, F>
+where
+  S: Observable<'a>,
+  F: 'a + FnMut(&S::Item) -> bool,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut predicate = self.predicate;
+    let mut skipping = true;
+    self.source.subscribe_return_state(
+      move |v| {
+        if skipping && predicate(&v) {
+          return OState::Next;
+        }
+        skipping = false;
+        next(v)
+      },
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{creation::from_iter, ops::SkipWhile, testing::TestObserver};
+
+  #[test]
+  fn emits_from_first_failing_item() {
+    let observer = TestObserver::<_, ()>::new();
+    observer
+      .subscribe_to(from_iter(vec![0, 1, 5, 2, 6]).skip_while(|v| *v < 3));
+    observer.assert_values(&[5, 2, 6]);
+    observer.assert_completed();
+  }
+}
@@ -0,0 +1,338 @@
+use crate::{ErrComplete, OState, Observable, Subscription};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// Maps each item to an inner observable and emits the items of the most
+/// recent one only: a new item unsubscribes the current inner observable
+/// before subscribing the next one.
+///
+/// The stream completes after the source and the current inner observable
+/// have completed, and fails as soon as either fails. The returned
+/// subscription owns both the source and the current inner subscription.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{ops::{Map, SwitchMap}, Observable, Observer, Subject};
+/// # use std::cell::RefCell;
+/// let received = RefCell::new(vec![]);
+/// let queries = Subject::<&str, ()>::new();
+/// let results = Subject::<&str, ()>::new();
+/// let r = results.clone();
+/// queries
+///   .clone()
+///   .switch_map(move |q| r.clone().map(move |res| format!("{}: {}", q, res)))
+///   .subscribe_next(|v| received.borrow_mut().push(v));
+///
+/// queries.next("r");
+/// results.next("rust");
+/// queries.next("ru");
+/// results.next("rust");
+/// assert_eq!(*received.borrow(), vec!["r: rust", "ru: rust"]);
+/// ```
+pub trait SwitchMap<'a, T> {
+  fn switch_map<O, F>(self, f: F) -> SwitchMapOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(T) -> O + 'a,
+  {
+    SwitchMapOp {
+      source: self,
+      func: f,
+    }
+  }
+}
+
+impl<'a, T, O> SwitchMap<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct SwitchMapOp<S, F> {
+  source: S,
+  func: F,
+}
+
+impl<'a, S, F, O> Observable<'a> for SwitchMapOp<S, F>
+where
+  S: Observable<'a>,
+  S::Unsubscribe: 'a,
+  F: 'a + FnMut(S::Item) -> O,
+  O: Observable<'a, Err = S::Err>,
+  O::Unsubscribe: 'a,
+{
+  type Item = O::Item;
+  type Err = S::Err;
+  type Unsubscribe = SwitchMapSubscription<S::Unsubscribe, O::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    subscribe_one_inner(self.source, self.func, false, next, err_or_complete)
+  }
+}
+
+/// Subscribes `source`, keeping a single inner observable active: a new
+/// item either replaces the active inner observable, or is ignored if
+/// `exhaust` is set.
+pub(crate) fn subscribe_one_inner<'a, S, F, O, N, EC>(
+  source: S, mut func: F, exhaust: bool, next: N, err_or_complete: EC,
+) -> SwitchMapSubscription<S::Unsubscribe, O::Unsubscribe>
+where
+  S: Observable<'a>,
+  S::Unsubscribe: 'a,
+  F: 'a + FnMut(S::Item) -> O,
+  O: Observable<'a, Err = S::Err>,
+  O::Unsubscribe: 'a,
+  N: 'a + FnMut(O::Item) -> OState,
+  EC: 'a + FnMut(&ErrComplete<S::Err>),
+{
+  let slots = Rc::new(SwitchMapSlots {
+    outer: RefCell::new(None),
+    inner: RefCell::new(None),
+    stopped: Cell::new(false),
+  });
+  let state = Rc::new(SwitchMapState {
+    slots: slots.clone(),
+    current: Cell::new(0),
+    inner_active: Cell::new(false),
+    outer_done: Cell::new(false),
+    next: RefCell::new(next),
+    err_or_complete: RefCell::new(err_or_complete),
+  });
+
+  let on_next = state.clone();
+  let on_ec = state;
+  let subscription = source.subscribe_return_state(
+    move |v| {
+      let busy = exhaust && on_next.inner_active.get();
+      if !on_next.slots.stopped.get() && !busy {
+        let inner = func(v);
+        on_next.switch_to(inner);
+      }
+      if on_next.slots.stopped.get() {
+        OState::Complete
+      } else {
+        OState::Next
+      }
+    },
+    move |ec| match ec {
+      ErrComplete::Complete => {
+        on_ec.outer_done.set(true);
+        if !on_ec.inner_active.get() {
+          on_ec.finish(ec);
+        }
+      }
+      ErrComplete::Err(_) => on_ec.finish(ec),
+    },
+  );
+  if slots.stopped.get() {
+    subscription.unsubscribe();
+  } else {
+    *slots.outer.borrow_mut() = Some(subscription);
+  }
+
+  SwitchMapSubscription { slots }
+}
+
+struct SwitchMapSlots<S, I> {
+  outer: RefCell<Option<S>>,
+  inner: RefCell<Option<I>>,
+  stopped: Cell<bool>,
+}
+
+impl<S: Subscription, I: Subscription> SwitchMapSlots<S, I> {
+  /// Unsubscribes everything, returns false if already stopped.
+  fn stop(&self) -> bool {
+    if self.stopped.replace(true) {
+      return false;
+    }
+    let outer = self.outer.borrow_mut().take();
+    if let Some(s) = outer {
+      s.unsubscribe();
+    }
+    let inner = self.inner.borrow_mut().take();
+    if let Some(s) = inner {
+      s.unsubscribe();
+    }
+    true
+  }
+}
+
+struct SwitchMapState<S, I, N, EC> {
+  slots: Rc<SwitchMapSlots<S, I>>,
+  /// Id of the current inner observable, notifications of older ones are
+  /// ignored.
+  current: Cell<usize>,
+  inner_active: Cell<bool>,
+  outer_done: Cell<bool>,
+  next: RefCell<N>,
+  err_or_complete: RefCell<EC>,
+}
+
+impl<S, I, N, EC> SwitchMapState<S, I, N, EC>
+where
+  S: Subscription,
+  I: Subscription,
+{
+  fn switch_to<'a, O, T, E>(self: &Rc<Self>, inner: O)
+  where
+    O: Observable<'a, Item = T, Err = E, Unsubscribe = I>,
+    N: 'a + FnMut(T) -> OState,
+    EC: 'a + FnMut(&ErrComplete<E>),
+    S: 'a,
+    I: 'a,
+  {
+    let id = self.current.get() + 1;
+    self.current.set(id);
+    let previous = self.slots.inner.borrow_mut().take();
+    if let Some(s) = previous {
+      s.unsubscribe();
+    }
+    self.inner_active.set(true);
+
+    let on_next = self.clone();
+    let on_ec = self.clone();
+    let subscription = inner.subscribe_return_state(
+      move |v| {
+        if on_next.slots.stopped.get() || on_next.current.get() != id {
+          return OState::Complete;
+        }
+        let state = (*on_next.next.borrow_mut())(v);
+        if state == OState::Complete {
+          on_next.slots.stop();
+        }
+        state
+      },
+      move |ec| {
+        if on_ec.current.get() != id {
+          return;
+        }
+        match ec {
+          ErrComplete::Complete => {
+            on_ec.inner_active.set(false);
+            if on_ec.outer_done.get() {
+              on_ec.finish(ec);
+            }
+          }
+          ErrComplete::Err(_) => on_ec.finish(ec),
+        }
+      },
+    );
+
+    let still_current = !self.slots.stopped.get()
+      && self.current.get() == id
+      && self.inner_active.get();
+    if still_current {
+      *self.slots.inner.borrow_mut() = Some(subscription);
+    } else {
+      subscription.unsubscribe();
+    }
+  }
+
+  fn finish<E>(&self, ec: &ErrComplete<E>)
+  where
+    EC: FnMut(&ErrComplete<E>),
+  {
+    if self.slots.stop() {
+      (*self.err_or_complete.borrow_mut())(ec);
+    }
+  }
+}
+
+/// Owns the subscription of the source and of the current inner observable,
+/// returned by `switch_map` and `exhaust_map`.
+pub struct SwitchMapSubscription<S, I> {
+  slots: Rc<SwitchMapSlots<S, I>>,
+}
+
+impl<S, I> Subscription for SwitchMapSubscription<S, I>
+where
+  S: Subscription,
+  I: Subscription,
+{
+  fn unsubscribe(self) { self.slots.stop(); }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, of, throw},
+    ops::{BoxIt, SwitchMap, Take},
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot, TestObserver},
+    Observer, Subject, Subscription,
+  };
+
+  #[test]
+  fn switches_to_latest_inner() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2)];
+    let queries = hot::<_, ()>(&scheduler, "-a--b---|", &values);
+    let s = scheduler.clone();
+    let results = queries.switch_map(move |v| cold(&s, "-x--x|", &[('x', v)]));
+    // the second result of `a` is cancelled by `b`, and completion waits
+    // for the inner observable of `b`.
+    expect_observable(&scheduler, results).to_be("--a--b--b|", &values);
+  }
+
+  #[test]
+  fn completes_when_outer_completes_last() {
+    let outer = Subject::<_, ()>::new();
+    let inner = Subject::new();
+    let for_outer = inner.clone();
+    let observer = TestObserver::<i32, ()>::new();
+    observer.subscribe_to(outer.clone().switch_map(move |_| for_outer.clone()));
+
+    outer.next(());
+    inner.next(1);
+    inner.complete();
+    observer.assert_no_terminal();
+    outer.complete();
+    observer.assert_values(&[1]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn inner_error_stops_outer() {
+    let outer = Subject::new();
+    let observer = TestObserver::<i32, _>::new();
+    observer.subscribe_to(outer.clone().switch_map(|v| {
+      if v < 2 {
+        of(v).box_it()
+      } else {
+        throw("boom").box_it()
+      }
+    }));
+    outer.next(1).next(2).next(3);
+    observer.assert_values(&[1]);
+    observer.assert_error(&"boom");
+  }
+
+  #[test]
+  fn unsubscribe_tears_down_outer_and_inner() {
+    let outer = Subject::<i32, ()>::new();
+    let inner = Subject::new();
+    let for_outer = inner.clone();
+    let observer = TestObserver::new();
+    let subscription = observer
+      .subscribe_to(outer.clone().switch_map(move |_| for_outer.clone()));
+    outer.next(0);
+    inner.next(1);
+    subscription.unsubscribe();
+
+    outer.next(0);
+    inner.next(2);
+    observer.assert_values(&[1]);
+    observer.assert_no_terminal();
+  }
+
+  #[test]
+  fn take_stops_endless_outer() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..).switch_map(|v| of(v * 2)).take(3));
+    observer.assert_values(&[0, 2, 4]);
+    observer.assert_completed();
+  }
+}
@@ -0,0 +1,170 @@
+use crate::{ErrComplete, OState, Observable};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Emits only the first `count` items of the source, then completes and
+/// stops the source at once, without waiting for another item.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::Take, Observable};
+/// let mut received = vec![];
+/// // the source never ends by itself.
+/// from_iter::<_, ()>(0..)
+///   .take(3)
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![0, 1, 2]);
+/// ```
+pub trait Take<'a> {
+  fn take(self, count: usize) -> TakeOp<Self>
+  where
+    Self: Sized,
+  {
+    TakeOp {
+      source: self,
+      count,
+    }
+  }
+
+  /// Emit only the first item, the same as `take(1)`. An empty source
+  /// completes without any item.
+  fn first(self) -> TakeOp<Self>
+  where
+    Self: Sized,
+  {
+    self.take(1)
+  }
+}
+
+impl<'a, O> Take<'a> for O where O: Observable<'a> {}
+
+pub struct TakeOp<S> {
+  source: S,
+  count: usize,
+}
+
+impl<'a, S> Observable<'a> for TakeOp<S>
+where
+  S: Observable<'a>,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  /// `take(0)` completes at once, without subscribing the source.
+  type Unsubscribe = Option<S::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, mut err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let count = self.count;
+    if count == 0 {
+      err_or_complete(&ErrComplete::Complete);
+      return None;
+    }
+
+    let err_or_complete = Rc::new(RefCell::new(err_or_complete));
+    let complete = err_or_complete.clone();
+    let mut taken = 0;
+    let subscription = self.source.subscribe_return_state(
+      move |v| {
+        taken += 1;
+        let state = next(v);
+        if taken < count {
+          return state;
+        }
+        if state == OState::Next {
+          (*complete.borrow_mut())(&ErrComplete::Complete);
+        }
+        OState::Complete
+      },
+      move |ec| (*err_or_complete.borrow_mut())(ec),
+    );
+    Some(subscription)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{create, empty, from_iter},
+    ops::{Map, Take},
+    testing::TestObserver,
+    Observer, Subject, Subscription,
+  };
+  use std::cell::Cell;
+  use std::rc::Rc;
+
+  #[test]
+  fn unsubscribes_subject_when_done() {
+    let observed = Rc::new(Cell::new(0));
+    let subject = Subject::<i32, ()>::new();
+    let observer = TestObserver::new();
+    let o = observed.clone();
+    observer.subscribe_to(
+      subject
+        .clone()
+        .map(move |v| {
+          o.set(o.get() + 1);
+          v
+        })
+        .take(2),
+    );
+
+    subject.next(1).next(2).next(3);
+    subject.complete();
+    observer.assert_values(&[1, 2]);
+    observer.assert_completed();
+    // the third item never left the subject.
+    assert_eq!(observed.get(), 2);
+  }
+
+  #[test]
+  fn stops_producer() {
+    let torn_down = Rc::new(Cell::new(false));
+    let t = torn_down.clone();
+    let source = create(move |subscriber| {
+      let mut i = 0;
+      while !subscriber.is_closed() {
+        subscriber.next(i);
+        i += 1;
+      }
+      move || t.set(true)
+    });
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(source.take(3));
+    observer.assert_values(&[0, 1, 2]);
+    observer.assert_completed();
+    assert!(torn_down.get());
+  }
+
+  #[test]
+  fn take_zero_and_first() {
+    let subject = Subject::<i32, ()>::new();
+    let none = TestObserver::new();
+    let subscription = none.subscribe_to(subject.clone().take(0));
+    none.assert_completed();
+    subscription.unsubscribe();
+
+    let first = TestObserver::<_, ()>::new();
+    first.subscribe_to(from_iter(5..).first());
+    first.assert_values(&[5]);
+    first.assert_completed();
+
+    let of_empty = TestObserver::<i32, ()>::new();
+    of_empty.subscribe_to(empty().first());
+    of_empty.assert_values(&[]);
+    of_empty.assert_completed();
+  }
+
+  #[test]
+  fn nested_takes() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..).take(5).take(2));
+    observer.assert_values(&[0, 1]);
+    observer.assert_completed();
+  }
+}
@@ -0,0 +1,140 @@
+use crate::{
+  ops::{
+    notifier::{subscribe_with_notifier, Notified},
+    NotifierSubscription,
+  },
+  ErrComplete, OState, Observable,
+};
+
+/// Emits the items of the source until `notifier` emits its first item,
+/// then completes and stops both.
+///
+/// An error of the notifier fails the stream, its completion is ignored.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   ops::TakeUntil, ErrComplete, Observable, Observer, Subject,
+/// # };
+/// # use std::cell::RefCell;
+/// let received = RefCell::new(vec![]);
+/// {
+///   let clicks = Subject::new();
+///   let stop = Subject::<(), ()>::new();
+///   clicks
+///     .clone()
+///     .take_until(stop.clone())
+///     .subscribe(|v| received.borrow_mut().push(v), |_| {});
+///
+///   clicks.next(1);
+///   stop.next(());
+///   clicks.next(2);
+/// }
+/// assert_eq!(received.into_inner(), vec![1]);
+/// ```
+pub trait TakeUntil<'a> {
+  fn take_until<N>(self, notifier: N) -> TakeUntilOp<Self, N>
+  where
+    Self: Sized,
+  {
+    TakeUntilOp {
+      source: self,
+      notifier,
+    }
+  }
+}
+
+impl<'a, O> TakeUntil<'a> for O where O: Observable<'a> {}
+
+pub struct TakeUntilOp<S, N> {
+  source: S,
+  notifier: N,
+}
+
+impl<'a, S, Notify> Observable<'a> for TakeUntilOp<S, Notify>
+where
+  S: Observable<'a>,
+  Notify: Observable<'a, Err = S::Err>,
+  S::Unsubscribe: 'a,
+  Notify::Unsubscribe: 'a,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = NotifierSubscription<S::Unsubscribe, Notify::Unsubscribe>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    subscribe_with_notifier(
+      self.source,
+      self.notifier,
+      |_| true,
+      || Notified::Complete,
+      next,
+      err_or_complete,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, interval, of},
+    ops::TakeUntil,
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot, TestObserver},
+    Observer, Subject,
+  };
+  use std::time::Duration;
+
+  #[test]
+  fn completes_when_notified() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3), ('x', 0)];
+    let source = hot::<_, ()>(&scheduler, "-a-b-c-|", &values);
+    let notifier = cold(&scheduler, "----x", &values);
+    expect_observable(&scheduler, source.take_until(notifier))
+      .to_be("-a-b|", &values);
+  }
+
+  #[test]
+  fn notifier_error_fails() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2)];
+    let source = hot(&scheduler, "-a-b-|", &values);
+    let notifier = cold(&scheduler, "--#", &values).with_error("boom");
+    expect_observable(&scheduler, source.take_until(notifier))
+      .with_error("boom")
+      .to_be("-a#", &values);
+  }
+
+  #[test]
+  fn stops_interval() {
+    let secs = Duration::from_secs;
+    let scheduler = TestScheduler::new();
+    let observer = TestObserver::<_, ()>::with_scheduler(&scheduler);
+    let stop = Subject::new();
+    observer.subscribe_to(
+      interval(secs(1), scheduler.clone()).take_until(stop.clone()),
+    );
+    scheduler.advance_by(secs(2));
+    stop.next(());
+    // nothing is scheduled anymore, so flush returns.
+    scheduler.flush();
+    observer.assert_values(&[0, 1]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn sync_notifier_never_subscribes_source() {
+    let observer = TestObserver::<i32, ()>::new();
+    observer.subscribe_to(from_iter(0..).take_until(of(())));
+    observer.assert_values(&[]);
+    observer.assert_completed();
+  }
+}
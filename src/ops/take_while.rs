@@ -0,0 +1,96 @@
+use crate::{ErrComplete, OState, Observable};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Emits the items of the source while they pass a predicate test, then
+/// completes on the first item failing it and stops the source.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{creation::from_iter, ops::TakeWhile, Observable};
+/// let mut received = vec![];
+/// from_iter::<_, ()>(vec![1, 3, 4, 5])
+///   .take_while(|v| v % 2 == 1)
+///   .subscribe_next(|v| received.push(v));
+/// assert_eq!(received, vec![1, 3]);
+/// ```
+pub trait TakeWhile<'a, T> {
+  fn take_while<F>(self, predicate: F) -> TakeWhileOp<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(&T) -> bool + 'a,
+  {
+    TakeWhileOp {
+      source: self,
+      predicate,
+    }
+  }
+}
+
+impl<'a, T, O> TakeWhile<'a, T> for O where O: Observable<'a, Item = T> {}
+
+pub struct TakeWhileOp<S, F> {
+  source: S,
+  predicate: F,
+}
+
+impl<'a, S, F> Observable<'a> for TakeWhileOp<S, F>
+where
+  S: Observable<'a>,
+  F: 'a + FnMut(&S::Item) -> bool,
+{
+  type Item = S::Item;
+  type Err = S::Err;
+  type Unsubscribe = S::Unsubscribe;
+
+  fn subscribe_return_state<N, EC>(
+    self, mut next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let mut predicate = self.predicate;
+    let err_or_complete = Rc::new(RefCell::new(err_or_complete));
+    let complete = err_or_complete.clone();
+    self.source.subscribe_return_state(
+      move |v| {
+        if predicate(&v) {
+          next(v)
+        } else {
+          (*complete.borrow_mut())(&ErrComplete::Complete);
+          OState::Complete
+        }
+      },
+      move |ec| (*err_or_complete.borrow_mut())(ec),
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::from_iter, ops::TakeWhile, testing::TestObserver, Observer,
+    Subject,
+  };
+
+  #[test]
+  fn completes_on_first_failing_item() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(0..).take_while(|v| *v < 3));
+    observer.assert_values(&[0, 1, 2]);
+    observer.assert_completed();
+  }
+
+  #[test]
+  fn passes_error_through() {
+    let subject = Subject::new();
+    let observer = TestObserver::new();
+    observer.subscribe_to(subject.clone().take_while(|v| *v < 3));
+    subject.next(1);
+    subject.err("boom");
+    observer.assert_values(&[1]);
+    observer.assert_error(&"boom");
+  }
+}
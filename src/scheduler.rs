@@ -0,0 +1,129 @@
+use crate::Subscription;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod trampoline;
+pub use trampoline::TrampolineScheduler;
+mod new_thread;
+pub use new_thread::NewThreadScheduler;
+mod test_scheduler;
+pub use test_scheduler::TestScheduler;
+
+/// Schedules tasks to run now, after a delay, or periodically.
+///
+/// Every task receives the subscription of its own schedule, so it can
+/// cancel itself, e.g. a periodic task stopping after some runs.
+///
+/// Tasks may run on another thread, so they must be `Send` and `'static`.
+/// Schedulers running tasks on the current thread implement
+/// `LocalScheduler` too, which accepts any task.
+pub trait Scheduler {
+  /// Run `task` once, after `delay` if any.
+  fn schedule<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + Send + 'static;
+
+  /// Run `task` every `period`, the first run happens after one period.
+  fn schedule_periodic<T>(
+    &self, task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + Send + 'static;
+}
+
+/// A scheduler running tasks on the current thread, the tasks only need to
+/// live as long as `'a`.
+pub trait LocalScheduler<'a> {
+  /// Run `task` once, after `delay` if any.
+  fn schedule_local<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + 'a;
+
+  /// Run `task` every `period`, the first run happens after one period.
+  fn schedule_periodic_local<T>(
+    &self, task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + 'a;
+}
+
+/// The handle of a scheduled task, unsubscribing it cancels every run that
+/// has not started yet.
+#[derive(Clone, Default)]
+pub struct SchedulerSubscription {
+  cancelled: Arc<AtomicBool>,
+}
+
+impl SchedulerSubscription {
+  pub fn new() -> Self { Self::default() }
+
+  pub fn is_closed(&self) -> bool { self.cancelled.load(Ordering::SeqCst) }
+}
+
+impl Subscription for SchedulerSubscription {
+  fn unsubscribe(self) { self.cancelled.store(true, Ordering::SeqCst); }
+}
+
+/// Tasks waiting for their due time `D`, popped in order of due time, then
+/// in scheduling order.
+pub(crate) struct TaskQueue<'a, D> {
+  next_seq: usize,
+  tasks: BinaryHeap<QueuedTask<'a, D>>,
+}
+
+impl<'a, D: Ord> TaskQueue<'a, D> {
+  pub(crate) fn push(&mut self, due: D, task: Box<dyn FnOnce() + 'a>) {
+    let seq = self.next_seq;
+    self.next_seq += 1;
+    self.tasks.push(QueuedTask { due, seq, task });
+  }
+
+  pub(crate) fn pop(&mut self) -> Option<(D, Box<dyn FnOnce() + 'a>)> {
+    self.tasks.pop().map(|t| (t.due, t.task))
+  }
+
+  pub(crate) fn peek_due(&self) -> Option<&D> {
+    self.tasks.peek().map(|t| &t.due)
+  }
+}
+
+impl<'a, D: Ord> Default for TaskQueue<'a, D> {
+  fn default() -> Self {
+    TaskQueue {
+      next_seq: 0,
+      tasks: BinaryHeap::new(),
+    }
+  }
+}
+
+struct QueuedTask<'a, D> {
+  due: D,
+  seq: usize,
+  task: Box<dyn FnOnce() + 'a>,
+}
+
+impl<'a, D: Ord> PartialEq for QueuedTask<'a, D> {
+  fn eq(&self, other: &Self) -> bool { self.cmp(other) == CmpOrdering::Equal }
+}
+
+impl<'a, D: Ord> Eq for QueuedTask<'a, D> {}
+
+impl<'a, D: Ord> PartialOrd for QueuedTask<'a, D> {
+  fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<'a, D: Ord> Ord for QueuedTask<'a, D> {
+  // `BinaryHeap` pops the greatest first, so the earliest is the greatest.
+  fn cmp(&self, other: &Self) -> CmpOrdering {
+    (&other.due, other.seq).cmp(&(&self.due, self.seq))
+  }
+}
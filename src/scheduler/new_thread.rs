@@ -0,0 +1,105 @@
+use crate::scheduler::{Scheduler, SchedulerSubscription};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs every scheduled task on a new thread, `schedule` never blocks.
+#[derive(Clone, Copy, Default)]
+pub struct NewThreadScheduler;
+
+impl Scheduler for NewThreadScheduler {
+  fn schedule<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + Send + 'static,
+  {
+    let subscription = SchedulerSubscription::new();
+    let for_task = subscription.clone();
+    thread::spawn(move || {
+      if let Some(delay) = delay {
+        thread::sleep(delay);
+      }
+      if !for_task.is_closed() {
+        task(for_task);
+      }
+    });
+    subscription
+  }
+
+  fn schedule_periodic<T>(
+    &self, mut task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + Send + 'static,
+  {
+    let subscription = SchedulerSubscription::new();
+    let for_task = subscription.clone();
+    thread::spawn(move || {
+      let mut due = Instant::now();
+      loop {
+        due += period;
+        let now = Instant::now();
+        if due > now {
+          thread::sleep(due - now);
+        }
+        if for_task.is_closed() {
+          break;
+        }
+        task(for_task.clone());
+      }
+    });
+    subscription
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    scheduler::{NewThreadScheduler, Scheduler},
+    Subscription,
+  };
+  use std::sync::mpsc::channel;
+  use std::thread;
+  use std::time::Duration;
+
+  #[test]
+  fn runs_on_another_thread() {
+    let (tx, rx) = channel();
+    let current = thread::current().id();
+    NewThreadScheduler.schedule(
+      move |_| tx.send(thread::current().id()).unwrap(),
+      Some(Duration::from_millis(1)),
+    );
+    assert_ne!(rx.recv().unwrap(), current);
+  }
+
+  #[test]
+  fn periodic_until_cancelled() {
+    let (tx, rx) = channel();
+    let mut runs = 0;
+    NewThreadScheduler.schedule_periodic(
+      move |subscription| {
+        runs += 1;
+        tx.send(runs).unwrap();
+        if runs == 3 {
+          subscription.unsubscribe();
+        }
+      },
+      Duration::from_millis(1),
+    );
+    // the sender is dropped with the task once cancelled.
+    assert_eq!(rx.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn cancel_before_due() {
+    let (tx, rx) = channel::<()>();
+    NewThreadScheduler
+      .schedule(
+        move |_| tx.send(()).unwrap(),
+        Some(Duration::from_millis(10)),
+      )
+      .unsubscribe();
+    assert!(rx.recv().is_err());
+  }
+}
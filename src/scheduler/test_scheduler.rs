@@ -0,0 +1,274 @@
+use crate::scheduler::{
+  LocalScheduler, Scheduler, SchedulerSubscription, TaskQueue,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// A scheduler on a virtual clock, for deterministic tests of anything
+/// time related.
+///
+/// Scheduling never runs a task, the clock starts at zero and only moves
+/// when the test advances it, running every task due on the way, each with
+/// the clock set to its due time. No test ever sleeps.
+///
+/// Clones share the same clock and queue.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   creation::interval, scheduler::TestScheduler, Observable, Subscription,
+/// # };
+/// # use std::cell::RefCell;
+/// # use std::time::Duration;
+/// let ticks = RefCell::new(vec![]);
+/// let scheduler = TestScheduler::new();
+/// let ticking = interval::<_, ()>(Duration::from_secs(1), scheduler.clone())
+///   .subscribe_next(|v| ticks.borrow_mut().push(v));
+///
+/// scheduler.advance_by(Duration::from_millis(2500));
+/// assert_eq!(*ticks.borrow(), vec![0, 1]);
+/// scheduler.advance_to(Duration::from_secs(3));
+/// assert_eq!(*ticks.borrow(), vec![0, 1, 2]);
+/// ticking.unsubscribe();
+/// ```
+#[derive(Clone, Default)]
+pub struct TestScheduler<'a> {
+  clock: Rc<Cell<Duration>>,
+  queue: Rc<RefCell<TaskQueue<'a, Duration>>>,
+}
+
+impl<'a> TestScheduler<'a> {
+  pub fn new() -> Self { Self::default() }
+
+  /// The virtual time elapsed since the scheduler was created.
+  pub fn now(&self) -> Duration { self.clock.get() }
+
+  /// The clock itself, for recorders timestamping what they receive.
+  pub(crate) fn clock(&self) -> Rc<Cell<Duration>> { self.clock.clone() }
+
+  /// Move the clock forward by `duration`, running the tasks due meanwhile.
+  pub fn advance_by(&self, duration: Duration) {
+    self.advance_to(self.now() + duration)
+  }
+
+  /// Move the clock to `time`, running the tasks due until then, including
+  /// the ones they schedule. The clock never goes back, an earlier `time`
+  /// does nothing.
+  pub fn advance_to(&self, time: Duration) {
+    while let Some(task) = self.pop_due(|due| due <= time) {
+      task();
+    }
+    if self.now() < time {
+      self.clock.set(time);
+    }
+  }
+
+  /// Run every queued task, including the ones they schedule, the clock ends
+  /// at the due time of the last task.
+  ///
+  /// # Panics
+  ///
+  /// A periodic task still running after 100 000 tasks ran is considered
+  /// endless, so `flush` panics rather than hang.
+  pub fn flush(&self) {
+    const LIMIT: usize = 100_000;
+    let mut ran = 0;
+    while let Some(task) = self.pop_due(|_| true) {
+      assert!(
+        ran < LIMIT,
+        "more than {} tasks flushed, is one endless?",
+        LIMIT
+      );
+      ran += 1;
+      task();
+    }
+  }
+
+  fn pop_due<F>(&self, is_due: F) -> Option<Box<dyn FnOnce() + 'a>>
+  where
+    F: FnOnce(Duration) -> bool,
+  {
+    let mut queue = self.queue.borrow_mut();
+    let due = *queue.peek_due()?;
+    if !is_due(due) {
+      return None;
+    }
+    let (due, task) = queue.pop()?;
+    if self.now() < due {
+      self.clock.set(due);
+    }
+    Some(task)
+  }
+
+  fn enqueue_periodic<T>(
+    &self, mut task: T, period: Duration, due: Duration,
+    subscription: SchedulerSubscription,
+  ) where
+    T: FnMut(SchedulerSubscription) + 'a,
+  {
+    let scheduler = self.clone();
+    self.queue.borrow_mut().push(
+      due,
+      Box::new(move || {
+        if subscription.is_closed() {
+          return;
+        }
+        task(subscription.clone());
+        if !subscription.is_closed() {
+          scheduler.enqueue_periodic(task, period, due + period, subscription);
+        }
+      }),
+    );
+  }
+}
+
+impl<'a> LocalScheduler<'a> for TestScheduler<'a> {
+  fn schedule_local<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + 'a,
+  {
+    let subscription = SchedulerSubscription::new();
+    let for_task = subscription.clone();
+    let due = self.now() + delay.unwrap_or_default();
+    self.queue.borrow_mut().push(
+      due,
+      Box::new(move || {
+        if !for_task.is_closed() {
+          task(for_task);
+        }
+      }),
+    );
+    subscription
+  }
+
+  fn schedule_periodic_local<T>(
+    &self, task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + 'a,
+  {
+    let subscription = SchedulerSubscription::new();
+    let due = self.now() + period;
+    self.enqueue_periodic(task, period, due, subscription.clone());
+    subscription
+  }
+}
+
+impl<'a> Scheduler for TestScheduler<'a> {
+  fn schedule<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + Send + 'static,
+  {
+    self.schedule_local(task, delay)
+  }
+
+  fn schedule_periodic<T>(
+    &self, task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + Send + 'static,
+  {
+    self.schedule_periodic_local(task, period)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::timer,
+    scheduler::{LocalScheduler, TestScheduler},
+    ErrComplete, Observable, Subscription,
+  };
+  use std::cell::RefCell;
+  use std::time::Duration;
+
+  #[test]
+  fn tasks_run_at_their_virtual_time() {
+    let ms = Duration::from_millis;
+    let runs = RefCell::new(vec![]);
+    let scheduler = TestScheduler::new();
+    let s = scheduler.clone();
+    let runs_ref = &runs;
+    scheduler.schedule_local(
+      move |_| {
+        runs_ref.borrow_mut().push(("a", s.now()));
+        let inner = s.clone();
+        s.schedule_local(
+          move |_| runs_ref.borrow_mut().push(("b", inner.now())),
+          Some(ms(5)),
+        );
+      },
+      Some(ms(10)),
+    );
+    scheduler
+      .schedule_local(|_| runs.borrow_mut().push(("c", ms(0))), Some(ms(30)))
+      .unsubscribe();
+
+    scheduler.advance_by(ms(9));
+    assert!(runs.borrow().is_empty());
+    assert_eq!(scheduler.now(), ms(9));
+    scheduler.advance_to(ms(20));
+    assert_eq!(*runs.borrow(), vec![("a", ms(10)), ("b", ms(15))]);
+    assert_eq!(scheduler.now(), ms(20));
+
+    // going back in time does nothing.
+    scheduler.advance_to(ms(1));
+    assert_eq!(scheduler.now(), ms(20));
+    scheduler.flush();
+    assert_eq!(runs.borrow().len(), 2);
+  }
+
+  #[test]
+  fn periodic() {
+    let ms = Duration::from_millis;
+    let runs = RefCell::new(vec![]);
+    let scheduler = TestScheduler::new();
+    let s = scheduler.clone();
+    let runs_ref = &runs;
+    scheduler.schedule_periodic_local(
+      move |subscription| {
+        let mut runs = runs_ref.borrow_mut();
+        runs.push(s.now());
+        if runs.len() == 3 {
+          subscription.unsubscribe();
+        }
+      },
+      ms(10),
+    );
+    scheduler.advance_by(ms(25));
+    assert_eq!(*runs.borrow(), vec![ms(10), ms(20)]);
+    scheduler.flush();
+    assert_eq!(*runs.borrow(), vec![ms(10), ms(20), ms(30)]);
+    assert_eq!(scheduler.now(), ms(30));
+  }
+
+  #[test]
+  #[should_panic(expected = "is one endless?")]
+  fn flush_endless_periodic() {
+    let scheduler = TestScheduler::new();
+    scheduler.schedule_periodic_local(|_| {}, Duration::from_secs(1));
+    scheduler.flush();
+  }
+
+  #[test]
+  fn timer_in_virtual_time() {
+    let notifications = RefCell::new(vec![]);
+    let scheduler = TestScheduler::new();
+    timer(Duration::from_secs(60), scheduler.clone()).subscribe(
+      |v| notifications.borrow_mut().push(format!("{}", v)),
+      |ec: &ErrComplete<()>| {
+        notifications.borrow_mut().push(format!("{:?}", ec))
+      },
+    );
+    scheduler.advance_by(Duration::from_secs(59));
+    assert!(notifications.borrow().is_empty());
+    scheduler.advance_by(Duration::from_secs(1));
+    assert_eq!(*notifications.borrow(), vec!["0", "Complete"]);
+  }
+}
@@ -0,0 +1,252 @@
+use crate::scheduler::{
+  LocalScheduler, Scheduler, SchedulerSubscription, TaskQueue,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs tasks on the current thread.
+///
+/// A task scheduled while no task of this scheduler is running runs at once,
+/// blocking the thread until its due time. A task scheduled from inside a
+/// running task is queued instead, and runs after the current one returns,
+/// so recursive scheduling never grows the stack. Queued tasks run in order
+/// of due time, then in scheduling order.
+///
+/// A periodic task never blocks the caller, else subscribing an `interval`
+/// would never return its subscription. Scheduled while idle, it's only
+/// queued, and nothing runs it until [`run`](#method.run) is called, or
+/// until a one-shot task is scheduled while idle, which runs the whole
+/// queue. A one-shot task scheduled while idle, delayed or not, always runs
+/// at once.
+///
+/// Clones share the same queue.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::scheduler::{LocalScheduler, TrampolineScheduler};
+/// # use std::cell::RefCell;
+/// let order = RefCell::new(vec![]);
+/// {
+///   let scheduler = TrampolineScheduler::new();
+///   // a task can't borrow the scheduler holding it, so it takes a clone.
+///   let inner = scheduler.clone();
+///   let order = &order;
+///   scheduler.schedule_local(
+///     move |_| {
+///       inner.schedule_local(move |_| order.borrow_mut().push(2), None);
+///       order.borrow_mut().push(1);
+///     },
+///     None,
+///   );
+/// }
+/// assert_eq!(order.into_inner(), vec![1, 2]);
+/// ```
+#[derive(Clone, Default)]
+pub struct TrampolineScheduler<'a> {
+  state: Rc<RefCell<TrampolineState<'a>>>,
+}
+
+#[derive(Default)]
+struct TrampolineState<'a> {
+  running: bool,
+  queue: TaskQueue<'a, Instant>,
+}
+
+impl<'a> TrampolineScheduler<'a> {
+  pub fn new() -> Self { Self::default() }
+
+  /// Run the queued tasks until none is left, unless already running.
+  pub fn run(&self) {
+    {
+      let mut state = self.state.borrow_mut();
+      if state.running {
+        return;
+      }
+      state.running = true;
+    }
+
+    loop {
+      let next = self.state.borrow_mut().queue.pop();
+      match next {
+        Some((due, task)) => {
+          let now = Instant::now();
+          if due > now {
+            thread::sleep(due - now);
+          }
+          task();
+        }
+        None => break,
+      }
+    }
+    self.state.borrow_mut().running = false;
+  }
+
+  fn enqueue(&self, due: Instant, task: Box<dyn FnOnce() + 'a>) {
+    self.state.borrow_mut().queue.push(due, task);
+  }
+
+  fn enqueue_periodic<T>(
+    &self, mut task: T, period: Duration, due: Instant,
+    subscription: SchedulerSubscription,
+  ) where
+    T: FnMut(SchedulerSubscription) + 'a,
+  {
+    let scheduler = self.clone();
+    self.enqueue(
+      due,
+      Box::new(move || {
+        if subscription.is_closed() {
+          return;
+        }
+        task(subscription.clone());
+        if !subscription.is_closed() {
+          scheduler.enqueue_periodic(task, period, due + period, subscription);
+        }
+      }),
+    );
+  }
+}
+
+impl<'a> LocalScheduler<'a> for TrampolineScheduler<'a> {
+  fn schedule_local<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + 'a,
+  {
+    let subscription = SchedulerSubscription::new();
+    let for_task = subscription.clone();
+    let due = Instant::now() + delay.unwrap_or_default();
+    self.enqueue(
+      due,
+      Box::new(move || {
+        if !for_task.is_closed() {
+          task(for_task);
+        }
+      }),
+    );
+    self.run();
+    subscription
+  }
+
+  fn schedule_periodic_local<T>(
+    &self, task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + 'a,
+  {
+    let subscription = SchedulerSubscription::new();
+    let due = Instant::now() + period;
+    self.enqueue_periodic(task, period, due, subscription.clone());
+    subscription
+  }
+}
+
+impl<'a> Scheduler for TrampolineScheduler<'a> {
+  fn schedule<T>(
+    &self, task: T, delay: Option<Duration>,
+  ) -> SchedulerSubscription
+  where
+    T: FnOnce(SchedulerSubscription) + Send + 'static,
+  {
+    self.schedule_local(task, delay)
+  }
+
+  fn schedule_periodic<T>(
+    &self, task: T, period: Duration,
+  ) -> SchedulerSubscription
+  where
+    T: FnMut(SchedulerSubscription) + Send + 'static,
+  {
+    self.schedule_periodic_local(task, period)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::interval,
+    scheduler::{LocalScheduler, TrampolineScheduler},
+    Observable, Subscription,
+  };
+  use std::cell::RefCell;
+  use std::time::{Duration, Instant};
+
+  #[test]
+  fn nested_tasks_are_queued_by_due_time() {
+    let order = RefCell::new(vec![]);
+    {
+      let scheduler = TrampolineScheduler::new();
+      let inner = scheduler.clone();
+      let order = &order;
+      scheduler.schedule_local(
+        move |_| {
+          let ms = Duration::from_millis;
+          inner
+            .schedule_local(move |_| order.borrow_mut().push(3), Some(ms(2)));
+          inner.schedule_local(move |_| order.borrow_mut().push(2), None);
+          let cancelled =
+            inner.schedule_local(move |_| order.borrow_mut().push(0), None);
+          cancelled.unsubscribe();
+          order.borrow_mut().push(1);
+        },
+        None,
+      );
+    }
+    assert_eq!(order.into_inner(), vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn delay_blocks_until_due() {
+    let start = Instant::now();
+    let mut ran_at = None;
+    TrampolineScheduler::new().schedule_local(
+      |_| ran_at = Some(Instant::now()),
+      Some(Duration::from_millis(10)),
+    );
+    assert!(ran_at.unwrap() - start >= Duration::from_millis(10));
+  }
+
+  #[test]
+  fn periodic_cancels_itself() {
+    let mut runs = 0;
+    let scheduler = TrampolineScheduler::new();
+    let subscription = scheduler.schedule_periodic_local(
+      |subscription| {
+        runs += 1;
+        if runs == 3 {
+          subscription.unsubscribe();
+        }
+      },
+      Duration::from_millis(1),
+    );
+    scheduler.run();
+    assert!(subscription.is_closed());
+    drop(scheduler);
+    assert_eq!(runs, 3);
+  }
+
+  #[test]
+  fn top_level_interval_returns_its_subscription() {
+    let ticks = RefCell::new(vec![]);
+    {
+      let scheduler = TrampolineScheduler::new();
+      let ms = Duration::from_millis;
+      let ticks = &ticks;
+      let ticking = interval::<_, ()>(ms(10), scheduler.clone())
+        .subscribe_next(move |v| ticks.borrow_mut().push(v));
+      assert!(ticks.borrow().is_empty());
+      scheduler.schedule_local(move |_| ticking.unsubscribe(), Some(ms(35)));
+    }
+    assert_eq!(ticks.into_inner(), vec![0, 1, 2]);
+
+    let scheduler = TrampolineScheduler::new();
+    interval::<_, ()>(Duration::from_millis(1), scheduler.clone())
+      .subscribe_next(|_| unreachable!())
+      .unsubscribe();
+    scheduler.run();
+  }
+}
@@ -1,68 +1,205 @@
-use crate::{ErrComplete, Observable, Observer, Subscription};
+use crate::{ErrComplete, OState, Observable, Observer, Subscription};
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
+use std::thread;
 
-pub(crate) type CallbackPtr<'a, T, E> = *const Callbacks<'a, T, E>;
+type NextCallback<'a, T> = Box<dyn FnMut(T) -> OState + 'a>;
+type ErrCompleteCallback<'a, E> = Box<dyn FnMut(&ErrComplete<E>) + 'a>;
 
 pub(crate) struct Callbacks<'a, T, E> {
-  on_next: Box<FnMut(&T) + 'a>,
-  on_err_or_complete: Box<FnMut(&ErrComplete<E>) + 'a>,
+  on_next: NextCallback<'a, T>,
+  on_err_or_complete: ErrCompleteCallback<'a, E>,
 }
 
-#[derive(Default)]
+/// Observers of a subject in subscribe order. Every observer is keyed by an
+/// id that is never reused, so a subscription always finds its own callbacks
+/// however the vector has been reallocated or shifted since.
+pub(crate) struct Observers<'a, T, E> {
+  next_id: usize,
+  entries: Vec<(usize, Callbacks<'a, T, E>)>,
+}
+
+impl<'a, T, E> Observers<'a, T, E> {
+  fn new_id(&mut self) -> usize {
+    let id = self.next_id;
+    self.next_id += 1;
+    id
+  }
+
+  fn add(&mut self, cbs: Callbacks<'a, T, E>) -> usize {
+    let id = self.new_id();
+    self.entries.push((id, cbs));
+    id
+  }
+
+  /// Remove the observer with `id`, return if it was found.
+  fn remove(&mut self, id: usize) -> bool {
+    // ids are handed out increasingly and entries are only ever appended, so
+    // the entries stay sorted by id.
+    match self.entries.binary_search_by_key(&id, |(id, _)| *id) {
+      Ok(idx) => {
+        self.entries.remove(idx);
+        true
+      }
+      Err(_) => false,
+    }
+  }
+}
+
+enum Notification<T, E> {
+  Next(T),
+  ErrComplete(ErrComplete<E>),
+}
+
+pub(crate) struct SubjectState<'a, T, E> {
+  observers: Observers<'a, T, E>,
+  /// Notifications waiting to be delivered, only the outermost `next`,
+  /// `complete` or `err` call drains it.
+  pending: VecDeque<Notification<T, E>>,
+  dispatching: bool,
+  /// Observers unsubscribed while they were taken out for a dispatch.
+  removed: Vec<usize>,
+  /// A complete or error has been emitted, everything emitted after it is
+  /// dropped.
+  stopped: bool,
+  /// The terminal notification, stored once its dispatch starts so late
+  /// observers can receive it.
+  terminal: Option<Rc<ErrComplete<E>>>,
+}
+
+/// A Subject is both an observable and an observer, it multicasts every
+/// notification it receives to all of its observers.
+///
+/// # Re-entrancy
+///
+/// Observers may call `next`, `complete` or `err` on the subject that is
+/// notifying them, subscribe new observers to it or unsubscribe from it,
+/// even while the subject is in the middle of a dispatch:
+///
+/// - A notification emitted during a dispatch is queued, and delivered once
+///   the current notification has reached every observer. Queued
+///   notifications are delivered in the order they were emitted.
+/// - An observer subscribed during a dispatch doesn't receive the
+///   notification being dispatched, but receives every notification queued
+///   after it.
+/// - An observer unsubscribed during a dispatch receives nothing more, not
+///   even the rest of the current dispatch.
+///
+/// # Termination
+///
+/// A subject follows the grammar `next* (complete | err)?`: once it has
+/// completed or failed, through any of its clones, everything emitted
+/// afterwards is dropped. An observer subscribing after the terminal
+/// notification has been delivered receives it immediately.
+///
+/// # Emitted values
+///
+/// Every observer receives its own clone of each value, so nothing an
+/// observer is given borrows from the subject. Share values that are
+/// expensive to clone by `Rc`.
+///
+/// A subject of references can't emit anything that doesn't outlive its
+/// observers:
+///
+/// ```compile_fail
+/// # use rx_rs::{ErrComplete, Observable, Observer, Subject};
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// let stash = Rc::new(RefCell::new(vec![]));
+/// let stash_clone = stash.clone();
+/// let subject = Subject::new();
+/// subject.clone().subscribe(
+///   move |v: &i32| stash_clone.borrow_mut().push(v),
+///   |_: &ErrComplete<()>| {},
+/// );
+/// {
+///   let short_lived = 1;
+///   subject.next(&short_lived);
+/// }
+/// println!("{:?}", stash.borrow());
+/// ```
 pub struct Subject<'a, T, E> {
-  cbs: Rc<RefCell<Vec<Callbacks<'a, T, E>>>>,
+  state: Rc<RefCell<SubjectState<'a, T, E>>>,
 }
 
 impl<'a, T, E> Clone for Subject<'a, T, E> {
   fn clone(&self) -> Self {
     Subject {
-      cbs: self.cbs.clone(),
+      state: self.state.clone(),
     }
   }
 }
 
-impl<'a, T: 'a, E: 'a> Observable<'a> for Subject<'a, T, E> {
-  type Item = &'a T;
+impl<'a, T: 'a, E: 'a> Default for Subject<'a, T, E> {
+  fn default() -> Self { Self::new() }
+}
+
+impl<'a, T: Clone + 'a, E: 'a> Observable<'a> for Subject<'a, T, E> {
+  type Item = T;
   type Err = E;
   type Unsubscribe = SubjectSubscription<'a, T, E>;
 
-  fn subscribe<N, EC>(self, next: N, err_or_complete: EC) -> Self::Unsubscribe
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
   where
-    N: FnMut(Self::Item) + 'a,
+    N: 'a + FnMut(Self::Item) -> OState,
     EC: 'a + FnMut(&ErrComplete<E>),
   {
-    let next: Box<FnMut(Self::Item)> = Box::new(next);
-    // of course, we know Self::Item and &'a T is the same type, but
-    // rust can't infer it, so, write an unsafe code to let rust know.
-    let next: Box<(dyn for<'r> std::ops::FnMut(&'r T) + 'a)> =
-      unsafe { std::mem::transmute(next) };
-    let cbs = Callbacks {
-      on_next: next,
-      on_err_or_complete: Box::new(err_or_complete),
+    let terminal = self.state.borrow().terminal.clone();
+    let id = if let Some(ec) = terminal {
+      let mut err_or_complete = err_or_complete;
+      err_or_complete(&ec);
+      self.state.borrow_mut().observers.new_id()
+    } else {
+      let cbs = Callbacks {
+        on_next: Box::new(next),
+        on_err_or_complete: Box::new(err_or_complete),
+      };
+      self.state.borrow_mut().observers.add(cbs)
     };
-    self.cbs.borrow_mut().push(cbs);
-    let ptr = self.cbs.borrow().last().unwrap() as CallbackPtr<'a, T, E>;
 
-    SubjectSubscription {
-      source: self,
-      callback: ptr,
-    }
+    SubjectSubscription { source: self, id }
   }
 }
 
 impl<'a, T: 'a, E: 'a> Subject<'a, T, E> {
   pub fn new() -> Subject<'a, T, E> {
     Subject {
-      cbs: Rc::new(RefCell::new(vec![])),
+      state: Rc::new(RefCell::new(SubjectState {
+        observers: Observers {
+          next_id: 0,
+          entries: vec![],
+        },
+        pending: VecDeque::new(),
+        dispatching: false,
+        removed: vec![],
+        stopped: false,
+        terminal: None,
+      })),
     }
   }
 
+  /// Return true if the subject has completed or failed, anything emitted
+  /// to it from now on is dropped.
+  pub fn is_stopped(&self) -> bool { self.state.borrow().stopped }
+
+  /// Return true if the subject has started to deliver its terminal
+  /// notification. A closed subject doesn't keep any observer, new observers
+  /// receive the terminal notification immediately.
+  pub fn is_closed(&self) -> bool { self.state.borrow().terminal.is_some() }
+
   /// Create a new subject from a stream, enabling multiple observers
   /// ("fork" the stream)
+  ///
+  /// The error is cloned into the subject, so it can be queued behind the
+  /// notifications not yet delivered.
   pub fn from_stream<S>(stream: S) -> Self
   where
     S: Observable<'a, Item = T, Err = E>,
+    T: Clone,
+    E: Clone,
   {
     let broadcast = Self::new();
     let for_next = broadcast.clone();
@@ -71,68 +208,467 @@ impl<'a, T: 'a, E: 'a> Subject<'a, T, E> {
       move |v| {
         for_next.next(v);
       },
-      move |ec| for_ec.ec(ec),
+      move |ec| for_ec.emit(Notification::ErrComplete(ec.clone())),
     );
 
     broadcast
   }
 
-  fn remove_callback(&mut self, ptr: CallbackPtr<'a, T, E>) {
-    self.cbs.borrow_mut().retain(|x| x as *const _ != ptr);
+  fn remove_callback(&self, id: usize) {
+    let mut state = self.state.borrow_mut();
+    if !state.observers.remove(id) && state.dispatching {
+      state.removed.push(id);
+    }
+  }
+}
+
+impl<'a, T: Clone, E> Subject<'a, T, E> {
+  fn emit(&self, notification: Notification<T, E>) {
+    {
+      let mut state = self.state.borrow_mut();
+      if state.stopped {
+        return;
+      }
+      if let Notification::ErrComplete(_) = notification {
+        state.stopped = true;
+      }
+      state.pending.push_back(notification);
+      if state.dispatching {
+        // the outermost dispatch will deliver it.
+        return;
+      }
+      state.dispatching = true;
+    }
+
+    loop {
+      // take the observers out, so callbacks can freely subscribe or
+      // unsubscribe while we are notifying them.
+      let (notification, observers) = {
+        let mut state = self.state.borrow_mut();
+        match state.pending.pop_front() {
+          Some(n) => (n, std::mem::take(&mut state.observers.entries)),
+          None => {
+            state.dispatching = false;
+            return;
+          }
+        }
+      };
+      let mut dispatch = Dispatch {
+        state: &self.state,
+        observers,
+        terminal: false,
+      };
+
+      match notification {
+        Notification::Next(v) => {
+          for (id, cbs) in dispatch.observers.iter_mut() {
+            if !self.state.borrow().removed.contains(id)
+              && (cbs.on_next)(v.clone()) == OState::Complete
+            {
+              // the observer is done, drop it like an unsubscribe.
+              self.state.borrow_mut().removed.push(*id);
+            }
+          }
+        }
+        Notification::ErrComplete(ec) => {
+          // the terminal notification is the last one ever queued, observers
+          // subscribing from now on receive it directly.
+          let ec = Rc::new(ec);
+          self.state.borrow_mut().terminal = Some(ec.clone());
+          dispatch.terminal = true;
+          for (id, cbs) in dispatch.observers.iter_mut() {
+            if !self.state.borrow().removed.contains(id) {
+              (cbs.on_err_or_complete)(&ec);
+            }
+          }
+        }
+      }
+    }
   }
+}
 
-  fn ec(&self, ec: &ErrComplete<E>) {
-    for cbs in self.cbs.borrow_mut().iter_mut() {
-      (cbs.on_err_or_complete)(&ec);
+/// The observers taken out of a subject for one dispatch. Dropping it puts
+/// them back, also when a callback panics, so a caught panic can't leave the
+/// subject without observers, or dispatching forever.
+struct Dispatch<'s, 'a, T, E> {
+  state: &'s RefCell<SubjectState<'a, T, E>>,
+  observers: Vec<(usize, Callbacks<'a, T, E>)>,
+  /// The terminal notification has been dispatched, the observers are
+  /// released instead of put back.
+  terminal: bool,
+}
+
+impl<'s, 'a, T, E> Drop for Dispatch<'s, 'a, T, E> {
+  fn drop(&mut self) {
+    let mut observers = std::mem::take(&mut self.observers);
+    let mut state = self.state.borrow_mut();
+    let removed = std::mem::take(&mut state.removed);
+    if thread::panicking() {
+      state.dispatching = false;
+    }
+    if self.terminal {
+      // released once the state is not borrowed anymore.
+      drop(state);
+      return;
     }
+    observers.retain(|(id, _)| !removed.contains(id));
+    // observers subscribed during the dispatch have greater ids, append them
+    // keeps the entries sorted.
+    observers.append(&mut state.observers.entries);
+    state.observers.entries = observers;
   }
 }
 
-impl<'a, T, E> Observer for Subject<'a, T, E> {
+impl<'a, T: Clone, E> Observer for Subject<'a, T, E> {
   type Item = T;
   type Err = E;
 
   fn next(&self, v: Self::Item) -> &Self {
-    for cbs in self.cbs.borrow_mut().iter_mut() {
-      (cbs.on_next)(&v);
-    }
+    self.emit(Notification::Next(v));
     self
   }
 
   fn complete(self) {
-    for cbs in self.cbs.borrow_mut().iter_mut() {
-      (cbs.on_err_or_complete)(&ErrComplete::Complete);
-    }
-    self.cbs.borrow_mut().clear();
+    self.emit(Notification::ErrComplete(ErrComplete::Complete));
   }
 
   fn err(self, err: Self::Err) {
-    let err = ErrComplete::Err(err);
-    for cbs in self.cbs.borrow_mut().iter_mut() {
-      (cbs.on_err_or_complete)(&err);
-    }
-    self.cbs.borrow_mut().clear();
+    self.emit(Notification::ErrComplete(ErrComplete::Err(err)));
   }
 }
 
+/// The handle `Subject::subscribe` returns, it removes exactly the observer it
+/// was created for when unsubscribed.
 pub struct SubjectSubscription<'a, T, E> {
   source: Subject<'a, T, E>,
-  callback: CallbackPtr<'a, T, E>,
+  id: usize,
 }
 
 impl<'a, T: 'a, E: 'a> Subscription for SubjectSubscription<'a, T, E> {
-  fn unsubscribe(mut self) { self.source.remove_callback(self.callback); }
+  fn unsubscribe(self) { self.source.remove_callback(self.id); }
 }
 
-#[test]
-fn base_data_flow() {
-  let mut i = 0;
-  {
-    let broadcast = Subject::new();
-    broadcast
+#[cfg(test)]
+mod test {
+  use super::SubjectSubscription;
+  use crate::{ErrComplete, Observable, Observer, Subject, Subscription};
+  use std::cell::RefCell;
+  use std::panic::{self, AssertUnwindSafe};
+  use std::rc::Rc;
+
+  #[test]
+  fn base_data_flow() {
+    let mut i = 0;
+    {
+      let broadcast = Subject::new();
+      broadcast
+        .clone()
+        .subscribe(|v| i = v * 2, |_: &ErrComplete<()>| {});
+      broadcast.next(1);
+    }
+    assert_eq!(i, 2);
+  }
+
+  #[test]
+  fn unsubscribe_removes_exactly_its_observer() {
+    const COUNT: usize = 500;
+    let received = Rc::new(RefCell::new(vec![0; COUNT]));
+    let subject = Subject::new();
+    let mut subscriptions = (0..COUNT)
+      .map(|idx| {
+        let received = received.clone();
+        Some(subject.clone().subscribe(
+          move |v| received.borrow_mut()[idx] += v,
+          |_: &ErrComplete<()>| {},
+        ))
+      })
+      .collect::<Vec<_>>();
+
+    // 7 is coprime with COUNT, so this visits every index once in a
+    // scattered order; unsubscribe the first half of that permutation.
+    let order = (0..COUNT).map(|i| i * 7 % COUNT).collect::<Vec<_>>();
+    let (removed, kept) = order.split_at(COUNT / 2);
+    removed.iter().for_each(|idx| {
+      subscriptions[*idx].take().unwrap().unsubscribe();
+    });
+    subject.next(1);
+
+    let received = received.borrow();
+    removed.iter().for_each(|idx| assert_eq!(received[*idx], 0));
+    kept.iter().for_each(|idx| assert_eq!(received[*idx], 1));
+  }
+
+  #[test]
+  fn interleaved_subscribe_and_unsubscribe() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::new();
+    let subscribe = |tag: usize| {
+      let received = received.clone();
+      subject.clone().subscribe(
+        move |_| received.borrow_mut().push(tag),
+        |_: &ErrComplete<()>| {},
+      )
+    };
+
+    let mut alive = vec![];
+    for round in 0..200 {
+      alive.push((round, subscribe(round)));
+      if round % 3 == 0 {
+        let (_, s) = alive.remove(alive.len() / 2);
+        s.unsubscribe();
+      }
+    }
+    subject.next(());
+
+    let expected = alive.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(*received.borrow(), expected);
+    alive.into_iter().for_each(|(_, s)| s.unsubscribe());
+
+    received.borrow_mut().clear();
+    subject.next(());
+    assert!(received.borrow().is_empty());
+  }
+
+  #[test]
+  fn emit_inside_callback_is_queued() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::new();
+    let feedback = subject.clone();
+    let r1 = received.clone();
+    subject.clone().subscribe(
+      move |v| {
+        r1.borrow_mut().push(("a", v));
+        if v < 3 {
+          feedback.next(v + 1);
+        }
+      },
+      |_: &ErrComplete<()>| {},
+    );
+    let r2 = received.clone();
+    subject.clone().subscribe(
+      move |v| r2.borrow_mut().push(("b", v)),
+      |_: &ErrComplete<()>| {},
+    );
+
+    subject.next(0);
+    assert_eq!(
+      *received.borrow(),
+      vec![
+        ("a", 0),
+        ("b", 0),
+        ("a", 1),
+        ("b", 1),
+        ("a", 2),
+        ("b", 2),
+        ("a", 3),
+        ("b", 3)
+      ]
+    );
+  }
+
+  #[test]
+  fn caught_panic_keeps_observers() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::<i32, ()>::new();
+    subject.clone().subscribe_next(|v| assert_ne!(v, 1, "boom"));
+    let r = received.clone();
+    subject
+      .clone()
+      .subscribe_next(move |v| r.borrow_mut().push(v));
+
+    let s = subject.clone();
+    let result = panic::catch_unwind(AssertUnwindSafe(move || {
+      s.next(1);
+    }));
+    assert!(result.is_err());
+    subject.next(2);
+    assert_eq!(*received.borrow(), vec![2]);
+  }
+
+  #[test]
+  fn subscribe_inside_callback() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::new();
+    let source = subject.clone();
+    let r1 = received.clone();
+    subject.clone().subscribe(
+      move |v: i32| {
+        if v == 0 {
+          let r2 = r1.clone();
+          source.clone().subscribe(
+            move |v| r2.borrow_mut().push(v),
+            |_: &ErrComplete<()>| {},
+          );
+          source.next(1);
+        }
+      },
+      |_: &ErrComplete<()>| {},
+    );
+
+    subject.next(0);
+    assert_eq!(*received.borrow(), vec![1]);
+    subject.next(2);
+    assert_eq!(*received.borrow(), vec![1, 2]);
+  }
+
+  #[test]
+  fn unsubscribe_inside_callback() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::new();
+    let subscription: Rc<RefCell<Option<SubjectSubscription<i32, ()>>>> =
+      Rc::new(RefCell::new(None));
+
+    let r1 = received.clone();
+    let s1 = subscription.clone();
+    *subscription.borrow_mut() = Some(subject.clone().subscribe(
+      move |v| {
+        r1.borrow_mut().push(("self", v));
+        if let Some(s) = s1.borrow_mut().take() {
+          s.unsubscribe();
+        }
+      },
+      |_: &ErrComplete<()>| {},
+    ));
+    let r2 = received.clone();
+    subject.clone().subscribe(
+      move |v| r2.borrow_mut().push(("other", v)),
+      |_: &ErrComplete<()>| {},
+    );
+
+    subject.next(0);
+    subject.next(1);
+    assert_eq!(
+      *received.borrow(),
+      vec![("self", 0), ("other", 0), ("other", 1)]
+    );
+  }
+
+  #[test]
+  fn unsubscribe_other_inside_callback() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::new();
+    let other: Rc<RefCell<Option<SubjectSubscription<i32, ()>>>> =
+      Rc::new(RefCell::new(None));
+
+    let o = other.clone();
+    subject.clone().subscribe(
+      move |_: i32| {
+        if let Some(s) = o.borrow_mut().take() {
+          s.unsubscribe();
+        }
+      },
+      |_: &ErrComplete<()>| {},
+    );
+    let r = received.clone();
+    *other.borrow_mut() = Some(
+      subject
+        .clone()
+        .subscribe(move |v| r.borrow_mut().push(v), |_: &ErrComplete<()>| {}),
+    );
+
+    subject.next(0);
+    assert!(received.borrow().is_empty());
+  }
+
+  #[test]
+  fn complete_inside_callback() {
+    let completed = Rc::new(RefCell::new(0));
+    let subject = Subject::new();
+    let for_complete = subject.clone();
+    subject.clone().subscribe(
+      move |_: i32| for_complete.clone().complete(),
+      |_: &ErrComplete<()>| {},
+    );
+    let c = completed.clone();
+    subject.clone().subscribe(
+      |_| {},
+      move |ec: &ErrComplete<()>| {
+        assert_eq!(ec, &ErrComplete::Complete);
+        *c.borrow_mut() += 1;
+      },
+    );
+
+    subject.next(0);
+    assert_eq!(*completed.borrow(), 1);
+  }
+
+  #[test]
+  fn emissions_after_complete_are_dropped() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let ec_count = Rc::new(RefCell::new(0));
+    let subject = Subject::new();
+    let r = received.clone();
+    let c = ec_count.clone();
+    subject.clone().subscribe(
+      move |v| r.borrow_mut().push(v),
+      move |_: &ErrComplete<&str>| *c.borrow_mut() += 1,
+    );
+
+    subject.next(1);
+    subject.clone().complete();
+    subject.next(2);
+    subject.clone().err("too late");
+    subject.clone().complete();
+
+    assert_eq!(*received.borrow(), vec![1]);
+    assert_eq!(*ec_count.borrow(), 1);
+  }
+
+  #[test]
+  fn late_subscriber_receives_terminal() {
+    let subject = Subject::<i32, _>::new();
+    subject.clone().err("boom");
+    assert!(subject.is_stopped());
+    assert!(subject.is_closed());
+
+    let received = Rc::new(RefCell::new(vec![]));
+    let r = received.clone();
+    subject.clone().subscribe(
+      |_| unreachable!("no value after error"),
+      move |ec: &ErrComplete<&str>| r.borrow_mut().push(ec.clone()),
+    );
+    assert_eq!(*received.borrow(), vec![ErrComplete::Err("boom")]);
+
+    let completed = Subject::<i32, ()>::new();
+    completed.clone().complete();
+    let received = Rc::new(RefCell::new(vec![]));
+    let r = received.clone();
+    completed
       .clone()
-      .subscribe(|v| i = *v * 2, |_: &ErrComplete<()>| {});
-    broadcast.next(1);
+      .subscribe(|_| {}, move |ec| r.borrow_mut().push(ec.clone()))
+      .unsubscribe();
+    assert_eq!(*received.borrow(), vec![ErrComplete::Complete]);
+  }
+
+  #[test]
+  fn subscribe_while_terminal_is_queued() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::new();
+    let source = subject.clone();
+    let r = received.clone();
+    subject.clone().subscribe(
+      move |v: i32| {
+        if v == 0 {
+          source.next(1);
+          source.clone().complete();
+          source.next(2);
+          assert!(source.is_stopped());
+          assert!(!source.is_closed());
+
+          let r = r.clone();
+          let r2 = r.clone();
+          source.clone().subscribe(
+            move |v| r.borrow_mut().push(format!("{}", v)),
+            move |_: &ErrComplete<()>| r2.borrow_mut().push("done".to_owned()),
+          );
+        }
+      },
+      |_: &ErrComplete<()>| {},
+    );
+
+    assert!(!subject.is_stopped());
+    subject.next(0);
+    assert!(subject.is_closed());
+    assert_eq!(*received.borrow(), vec!["1", "done"]);
   }
-  assert_eq!(i, 2);
 }
@@ -0,0 +1,373 @@
+use crate::Subscription;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Sources that are done by the time `subscribe` returns have nothing to
+/// unsubscribe.
+impl Subscription for () {
+  fn unsubscribe(self) {}
+}
+
+/// `None` stands for a source that was never subscribed.
+impl<S: Subscription> Subscription for Option<S> {
+  fn unsubscribe(self) {
+    if let Some(s) = self {
+      s.unsubscribe();
+    }
+  }
+}
+
+/// An object safe version of `Subscription`, every subscription implements
+/// it, so subscriptions of different types can be boxed together as
+/// `BoxSubscription`.
+pub trait SubscriptionBox {
+  fn unsubscribe_box(self: Box<Self>);
+}
+
+impl<S: Subscription> SubscriptionBox for S {
+  fn unsubscribe_box(self: Box<Self>) { (*self).unsubscribe() }
+}
+
+pub type BoxSubscription<'a> = Box<dyn SubscriptionBox + 'a>;
+
+impl<'a> Subscription for BoxSubscription<'a> {
+  fn unsubscribe(self) { self.unsubscribe_box() }
+}
+
+/// Unsubscribes the subscription it holds when dropped.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   ErrComplete, Observable, Observer, Subject, Subscription,
+/// #   SubscriptionGuard,
+/// # };
+/// struct Label<'a> {
+///   _text: SubscriptionGuard<
+///     rx_rs::subject::SubjectSubscription<'a, String, ()>,
+///   >,
+/// }
+///
+/// let text = Subject::new();
+/// let label = Label {
+///   _text: text
+///     .clone()
+///     .subscribe(|t| println!("{}", t), |_: &ErrComplete<()>| {})
+///     .unsubscribe_when_dropped(),
+/// };
+/// text.next("hello".to_owned());
+/// // the label stops listening to `text` when it's dropped.
+/// drop(label);
+/// text.next("nobody prints this".to_owned());
+/// ```
+#[must_use = "the subscription is unsubscribed as soon as the guard drops"]
+pub struct SubscriptionGuard<S: Subscription>(Option<S>);
+
+impl<S: Subscription> SubscriptionGuard<S> {
+  pub fn new(subscription: S) -> Self { SubscriptionGuard(Some(subscription)) }
+
+  /// Take the subscription back, it won't be unsubscribed on drop anymore.
+  pub fn into_inner(mut self) -> S {
+    self
+      .0
+      .take()
+      .expect("the guard holds its subscription until dropped")
+  }
+}
+
+impl<S: Subscription> Subscription for SubscriptionGuard<S> {
+  fn unsubscribe(mut self) {
+    if let Some(s) = self.0.take() {
+      s.unsubscribe();
+    }
+  }
+}
+
+impl<S: Subscription> Drop for SubscriptionGuard<S> {
+  fn drop(&mut self) {
+    if let Some(s) = self.0.take() {
+      s.unsubscribe();
+    }
+  }
+}
+
+/// Identifies a subscription added to a `CompositeSubscription`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionId(usize);
+
+struct CompositeState<'a> {
+  next_id: usize,
+  children: Vec<(usize, BoxSubscription<'a>)>,
+  closed: bool,
+}
+
+/// A group of subscriptions of any type, unsubscribed together.
+///
+/// Clones share the same group. Once the composite is unsubscribed it's
+/// closed: every subscription added afterwards is unsubscribed at once.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   ops::Merge, subscription::CompositeSubscription, ErrComplete,
+/// #   Observable, Observer, Subject, Subscription,
+/// # };
+/// let a = Subject::<i32, ()>::new();
+/// let b = Subject::new();
+///
+/// let subscriptions = CompositeSubscription::new();
+/// subscriptions.add(a.clone().subscribe(|v| println!("a: {}", v), |_| {}));
+/// subscriptions.add(
+///   a.clone().merge(b.clone()).subscribe(|v| println!("a|b: {}", v), |_| {}),
+/// );
+///
+/// // nothing is printed anymore.
+/// subscriptions.unsubscribe();
+/// a.next(1);
+/// b.next(2);
+/// ```
+pub struct CompositeSubscription<'a> {
+  state: Rc<RefCell<CompositeState<'a>>>,
+}
+
+impl<'a> Clone for CompositeSubscription<'a> {
+  fn clone(&self) -> Self {
+    CompositeSubscription {
+      state: self.state.clone(),
+    }
+  }
+}
+
+impl<'a> Default for CompositeSubscription<'a> {
+  fn default() -> Self { Self::new() }
+}
+
+impl<'a> CompositeSubscription<'a> {
+  pub fn new() -> Self {
+    CompositeSubscription {
+      state: Rc::new(RefCell::new(CompositeState {
+        next_id: 0,
+        children: vec![],
+        closed: false,
+      })),
+    }
+  }
+
+  /// Add a subscription to the group. If the group is already closed the
+  /// subscription is unsubscribed immediately.
+  pub fn add<S: Subscription + 'a>(&self, subscription: S) -> SubscriptionId {
+    let mut state = self.state.borrow_mut();
+    let id = state.next_id;
+    state.next_id += 1;
+    if state.closed {
+      drop(state);
+      subscription.unsubscribe();
+    } else {
+      state.children.push((id, Box::new(subscription)));
+    }
+    SubscriptionId(id)
+  }
+
+  /// Take a subscription out of the group without unsubscribing it, return
+  /// `None` if it's not in the group anymore.
+  pub fn remove(&self, id: SubscriptionId) -> Option<BoxSubscription<'a>> {
+    let mut state = self.state.borrow_mut();
+    let idx = state
+      .children
+      .binary_search_by_key(&id.0, |(id, _)| *id)
+      .ok()?;
+    Some(state.children.remove(idx).1)
+  }
+
+  /// Unsubscribe all the subscriptions in the group, the group stays open.
+  pub fn clear(&self) {
+    let children = std::mem::take(&mut self.state.borrow_mut().children);
+    children.into_iter().for_each(|(_, s)| s.unsubscribe());
+  }
+
+  pub fn len(&self) -> usize { self.state.borrow().children.len() }
+
+  pub fn is_empty(&self) -> bool { self.state.borrow().children.is_empty() }
+
+  pub fn is_closed(&self) -> bool { self.state.borrow().closed }
+}
+
+impl<'a> Subscription for CompositeSubscription<'a> {
+  fn unsubscribe(self) {
+    self.state.borrow_mut().closed = true;
+    self.clear();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{CompositeSubscription, SubscriptionId};
+  use crate::{ops::Merge, Observable, Observer, Subject, Subscription};
+  use std::cell::RefCell;
+  use std::rc::Rc;
+
+  #[test]
+  fn unsubscribe_on_drop() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::<i32, ()>::new();
+    {
+      let r = received.clone();
+      let _guard = subject
+        .clone()
+        .subscribe(move |v| r.borrow_mut().push(v), |_| {})
+        .unsubscribe_when_dropped();
+      subject.next(1);
+    }
+    subject.next(2);
+    assert_eq!(*received.borrow(), vec![1]);
+  }
+
+  #[test]
+  fn merge_subscription_guard() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let a = Subject::<i32, ()>::new();
+    let b = Subject::new();
+    let r = received.clone();
+    let guard = a
+      .clone()
+      .merge(b.clone())
+      .subscribe(move |v| r.borrow_mut().push(v), |_| {})
+      .unsubscribe_when_dropped();
+
+    a.next(1);
+    b.next(2);
+    drop(guard);
+    a.next(3);
+    b.next(4);
+    assert_eq!(*received.borrow(), vec![1, 2]);
+  }
+
+  #[test]
+  fn into_inner_keeps_subscription() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::<i32, ()>::new();
+    let r = received.clone();
+    let subscription = subject
+      .clone()
+      .subscribe(move |v| r.borrow_mut().push(v), |_| {})
+      .unsubscribe_when_dropped()
+      .into_inner();
+
+    subject.next(1);
+    subscription.unsubscribe();
+    subject.next(2);
+    assert_eq!(*received.borrow(), vec![1]);
+  }
+
+  #[test]
+  fn drop_guard_inside_callback() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::<i32, ()>::new();
+    let guard = Rc::new(RefCell::new(None));
+
+    let r = received.clone();
+    let g = guard.clone();
+    *guard.borrow_mut() = Some(
+      subject
+        .clone()
+        .subscribe(
+          move |v| {
+            r.borrow_mut().push(v);
+            g.borrow_mut().take();
+          },
+          |_| {},
+        )
+        .unsubscribe_when_dropped(),
+    );
+
+    subject.next(1);
+    subject.next(2);
+    assert_eq!(*received.borrow(), vec![1]);
+  }
+
+  #[test]
+  fn composite_of_different_subscriptions() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let a = Subject::<i32, ()>::new();
+    let b = Subject::new();
+    let composite = CompositeSubscription::new();
+
+    let r = received.clone();
+    composite.add(a.clone().subscribe(move |v| r.borrow_mut().push(v), |_| {}));
+    let r = received.clone();
+    composite.add(
+      a.clone()
+        .merge(b.clone())
+        .subscribe(move |v| r.borrow_mut().push(v * 10), |_| {}),
+    );
+    assert_eq!(composite.len(), 2);
+
+    a.next(1);
+    b.next(2);
+    composite.clone().unsubscribe();
+    a.next(3);
+    b.next(4);
+    assert_eq!(*received.borrow(), vec![1, 10, 20]);
+    assert!(composite.is_closed());
+    assert!(composite.is_empty());
+  }
+
+  #[test]
+  fn add_after_closed() {
+    let subject = Subject::<i32, ()>::new();
+    let composite = CompositeSubscription::new();
+    composite.clone().unsubscribe();
+
+    composite.add(subject.clone().subscribe(|_| unreachable!(), |_| {}));
+    assert!(composite.is_empty());
+    subject.next(1);
+  }
+
+  #[test]
+  fn remove_and_clear() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let subject = Subject::<i32, ()>::new();
+    let composite = CompositeSubscription::new();
+
+    let r = received.clone();
+    let kept = composite.add(
+      subject
+        .clone()
+        .subscribe(move |v| r.borrow_mut().push(v), |_| {}),
+    );
+    let r = received.clone();
+    composite.add(
+      subject
+        .clone()
+        .subscribe(move |v| r.borrow_mut().push(v * 10), |_| {}),
+    );
+
+    let kept = composite.remove(kept).unwrap();
+    assert!(composite.remove(SubscriptionId(0)).is_none());
+    composite.clear();
+    assert!(!composite.is_closed());
+    subject.next(1);
+    assert_eq!(*received.borrow(), vec![1]);
+
+    // a cleared composite still accepts subscriptions.
+    composite.add(kept);
+    composite.unsubscribe();
+    subject.next(2);
+    assert_eq!(*received.borrow(), vec![1]);
+  }
+
+  #[test]
+  fn nested_composite() {
+    let subject = Subject::<i32, ()>::new();
+    let parent = CompositeSubscription::new();
+    let child = CompositeSubscription::new();
+    child.add(subject.clone().subscribe(|_| unreachable!(), |_| {}));
+    parent.add(child.clone());
+
+    parent.unsubscribe();
+    assert!(child.is_closed());
+    subject.next(1);
+  }
+}
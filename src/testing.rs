@@ -0,0 +1,8 @@
+//! Helpers to test observables on the virtual clock of a `TestScheduler`.
+mod marbles;
+pub use marbles::{
+  cold, expect_observable, hot, ColdObservable, Expectation, HotObservable,
+  FRAME,
+};
+mod test_observer;
+pub use test_observer::TestObserver;
@@ -0,0 +1,482 @@
+//! Marble diagrams describe notifications over virtual time, one character
+//! per frame:
+//!
+//! - `-` a frame without notification.
+//! - any other letter or digit: an item, looked up in the `values` map.
+//! - `|` completion, `#` error.
+//! - `(ab)` notifications in the same frame, the group still spans as many
+//!   frames as it has characters.
+//! - `^` the frame a hot observable starts from, notifications before it
+//!   are skipped.
+//! - spaces are ignored, to align diagrams.
+use crate::{
+  scheduler::{LocalScheduler, TestScheduler},
+  subject::SubjectSubscription,
+  subscription::CompositeSubscription,
+  ErrComplete, OState, Observable, Observer, Subject, Subscription,
+};
+use std::cell::RefCell;
+use std::fmt::{Debug, Write};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// The virtual time a marble character stands for.
+pub const FRAME: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Marble<T, E> {
+  Next(T),
+  Err(E),
+  Complete,
+}
+
+type Timeline<T, E> = Vec<(usize, Marble<T, E>)>;
+type Recorded<T, E> = Rc<RefCell<Vec<(Duration, Marble<T, E>)>>>;
+
+struct Parsed<T> {
+  events: Timeline<T, ()>,
+  start: usize,
+}
+
+fn parse<T: Clone>(marbles: &str, values: &[(char, T)]) -> Parsed<T> {
+  let mut events = vec![];
+  let mut start = 0;
+  let mut frame = 0;
+  let mut group = None;
+  for c in marbles.chars() {
+    let at = group.unwrap_or(frame);
+    match c {
+      ' ' => continue,
+      '-' => {}
+      '(' => {
+        assert!(group.is_none(), "nested group in `{}`", marbles);
+        group = Some(frame);
+      }
+      ')' => {
+        assert!(group.take().is_some(), "unopened group in `{}`", marbles);
+      }
+      '^' => start = frame,
+      '|' => events.push((at, Marble::Complete)),
+      '#' => events.push((at, Marble::Err(()))),
+      c => {
+        let v = values
+          .iter()
+          .find(|(k, _)| *k == c)
+          .unwrap_or_else(|| panic!("no value for `{}` in `{}`", c, marbles));
+        events.push((at, Marble::Next(v.1.clone())));
+      }
+    }
+    frame += 1;
+  }
+  assert!(group.is_none(), "unclosed group in `{}`", marbles);
+  Parsed { events, start }
+}
+
+fn with_error<T, E: Clone>(
+  marble: Marble<T, ()>, err: Option<&E>,
+) -> Marble<T, E> {
+  match marble {
+    Marble::Next(v) => Marble::Next(v),
+    Marble::Complete => Marble::Complete,
+    Marble::Err(()) => Marble::Err(
+      err
+        .expect("a `#` needs an error value, set it by `with_error`")
+        .clone(),
+    ),
+  }
+}
+
+fn no_start(marbles: &str) {
+  assert!(
+    !marbles.contains('^'),
+    "`^` only marks where a hot observable starts, found in `{}`",
+    marbles
+  );
+}
+
+fn at_frame(frame: usize) -> Option<Duration> { Some(FRAME * frame as u32) }
+
+/// Creates an observable emitting `marbles` on `scheduler`, every
+/// subscription starts the diagram over from its own subscription time.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   ops::Map, scheduler::TestScheduler,
+/// #   testing::{cold, expect_observable},
+/// # };
+/// let scheduler = TestScheduler::new();
+/// let source = cold::<_, &str>(&scheduler, "-a-b-#", &[('a', 1), ('b', 2)])
+///   .with_error("boom");
+/// expect_observable(&scheduler, source.map(|v| v * 10))
+///   .with_error("boom")
+///   .to_be("-a-b-#", &[('a', 10), ('b', 20)]);
+/// ```
+pub fn cold<'a, T: Clone, E>(
+  scheduler: &TestScheduler<'a>, marbles: &str, values: &[(char, T)],
+) -> ColdObservable<'a, T, E> {
+  no_start(marbles);
+  ColdObservable {
+    scheduler: scheduler.clone(),
+    events: Rc::new(parse(marbles, values).events),
+    err: None,
+  }
+}
+
+pub struct ColdObservable<'a, T, E> {
+  scheduler: TestScheduler<'a>,
+  events: Rc<Timeline<T, ()>>,
+  err: Option<E>,
+}
+
+impl<'a, T, E: Clone> Clone for ColdObservable<'a, T, E> {
+  fn clone(&self) -> Self {
+    ColdObservable {
+      scheduler: self.scheduler.clone(),
+      events: self.events.clone(),
+      err: self.err.clone(),
+    }
+  }
+}
+
+impl<'a, T, E> ColdObservable<'a, T, E> {
+  /// The error a `#` emits.
+  pub fn with_error(mut self, err: E) -> Self {
+    self.err = Some(err);
+    self
+  }
+}
+
+impl<'a, T, E> Observable<'a> for ColdObservable<'a, T, E>
+where
+  T: Clone + 'a,
+  E: Clone + 'a,
+{
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = CompositeSubscription<'a>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    let observer = Rc::new(RefCell::new((next, err_or_complete)));
+    let subscriptions = CompositeSubscription::new();
+    for (frame, marble) in self.events.iter() {
+      let marble = with_error(marble.clone(), self.err.as_ref());
+      let observer = observer.clone();
+      let all = subscriptions.clone();
+      subscriptions.add(self.scheduler.schedule_local(
+        move |_| {
+          let (next, err_or_complete) = &mut *observer.borrow_mut();
+          match marble {
+            Marble::Next(v) => {
+              if next(v) == OState::Complete {
+                all.unsubscribe();
+              }
+            }
+            Marble::Err(err) => err_or_complete(&ErrComplete::Err(err)),
+            Marble::Complete => err_or_complete(&ErrComplete::Complete),
+          }
+        },
+        at_frame(*frame),
+      ));
+    }
+    subscriptions
+  }
+}
+
+/// Creates an observable emitting `marbles` on `scheduler` whether anyone
+/// subscribed or not, the diagram starts at `^`, or at the first frame.
+/// Subscribers only receive what's emitted after they subscribed.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{
+/// #   ops::Merge, scheduler::TestScheduler,
+/// #   testing::{cold, expect_observable, hot},
+/// # };
+/// let scheduler = TestScheduler::new();
+/// let values = [('a', 1), ('b', 2), ('c', 3)];
+/// let odd = hot::<_, ()>(&scheduler, "-a^--c-|", &values);
+/// let even = cold(&scheduler, "-b|", &values);
+/// expect_observable(&scheduler, odd.merge(even)).to_be("-b-c-|", &values);
+/// ```
+pub fn hot<'a, T, E>(
+  scheduler: &TestScheduler<'a>, marbles: &str, values: &[(char, T)],
+) -> HotObservable<'a, T, E>
+where
+  T: Clone + 'a,
+  E: Clone + 'a,
+{
+  let Parsed { events, start } = parse(marbles, values);
+  let subject = Subject::new();
+  let err = Rc::new(RefCell::new(None));
+  for (frame, marble) in events.into_iter().filter(|(f, _)| *f >= start) {
+    let subject = subject.clone();
+    let err = err.clone();
+    scheduler.schedule_local(
+      move |_| match with_error(marble, err.borrow().as_ref()) {
+        Marble::Next(v) => {
+          subject.next(v);
+        }
+        Marble::Err(err) => subject.err(err),
+        Marble::Complete => subject.complete(),
+      },
+      at_frame(frame - start),
+    );
+  }
+  HotObservable { subject, err }
+}
+
+pub struct HotObservable<'a, T, E> {
+  subject: Subject<'a, T, E>,
+  err: Rc<RefCell<Option<E>>>,
+}
+
+impl<'a, T, E> Clone for HotObservable<'a, T, E> {
+  fn clone(&self) -> Self {
+    HotObservable {
+      subject: self.subject.clone(),
+      err: self.err.clone(),
+    }
+  }
+}
+
+impl<'a, T, E> HotObservable<'a, T, E> {
+  /// The error a `#` emits.
+  pub fn with_error(self, err: E) -> Self {
+    *self.err.borrow_mut() = Some(err);
+    self
+  }
+}
+
+impl<'a, T: Clone + 'a, E: 'a> Observable<'a> for HotObservable<'a, T, E> {
+  type Item = T;
+  type Err = E;
+  type Unsubscribe = SubjectSubscription<'a, T, E>;
+
+  fn subscribe_return_state<N, EC>(
+    self, next: N, err_or_complete: EC,
+  ) -> Self::Unsubscribe
+  where
+    N: 'a + FnMut(Self::Item) -> OState,
+    EC: 'a + FnMut(&ErrComplete<Self::Err>),
+  {
+    self.subject.subscribe_return_state(next, err_or_complete)
+  }
+}
+
+/// Subscribes `observable` now and records what it emits, to compare with a
+/// marble diagram by `Expectation::to_be`.
+pub fn expect_observable<'a, O>(
+  scheduler: &TestScheduler<'a>, observable: O,
+) -> Expectation<'a, O::Item, O::Err>
+where
+  O: Observable<'a>,
+  O::Item: 'a,
+  O::Err: Clone + 'a,
+{
+  let recorded = Rc::new(RefCell::new(vec![]));
+  let on_next = (scheduler.clone(), recorded.clone());
+  let on_ec = (scheduler.clone(), recorded.clone());
+  observable.subscribe(
+    move |v| {
+      let (scheduler, recorded) = &on_next;
+      recorded
+        .borrow_mut()
+        .push((scheduler.now(), Marble::Next(v)));
+    },
+    move |ec| {
+      let (scheduler, recorded) = &on_ec;
+      let marble = match ec {
+        ErrComplete::Err(err) => Marble::Err(err.clone()),
+        ErrComplete::Complete => Marble::Complete,
+      };
+      recorded.borrow_mut().push((scheduler.now(), marble));
+    },
+  );
+  Expectation {
+    scheduler: scheduler.clone(),
+    subscribed_at: scheduler.now(),
+    recorded,
+    err: None,
+  }
+}
+
+pub struct Expectation<'a, T, E> {
+  scheduler: TestScheduler<'a>,
+  subscribed_at: Duration,
+  recorded: Recorded<T, E>,
+  err: Option<E>,
+}
+
+impl<'a, T, E> Expectation<'a, T, E> {
+  /// The error a `#` expects.
+  pub fn with_error(mut self, err: E) -> Self {
+    self.err = Some(err);
+    self
+  }
+
+  /// Flush the scheduler, then assert the observable emitted `marbles`,
+  /// counting frames from its subscription.
+  ///
+  /// # Panics
+  ///
+  /// Panics with both diagrams and the notifications side by side if they
+  /// differ.
+  pub fn to_be(self, marbles: &str, values: &[(char, T)])
+  where
+    T: Clone + PartialEq + Debug,
+    E: Clone + PartialEq + Debug,
+  {
+    no_start(marbles);
+    self.scheduler.flush();
+    let expected: Timeline<T, E> = parse(marbles, values)
+      .events
+      .into_iter()
+      .map(|(f, m)| (f, with_error(m, self.err.as_ref())))
+      .collect();
+    let actual: Timeline<T, E> = self
+      .recorded
+      .borrow()
+      .iter()
+      .map(|(t, m)| {
+        let frame = (*t - self.subscribed_at).as_nanos() / FRAME.as_nanos();
+        (frame as usize, m.clone())
+      })
+      .collect();
+    if expected != actual {
+      panic!("{}", diff(marbles, values, &expected, &actual));
+    }
+  }
+}
+
+fn diff<T: PartialEq + Debug, E: PartialEq + Debug>(
+  marbles: &str, values: &[(char, T)], expected: &Timeline<T, E>,
+  actual: &Timeline<T, E>,
+) -> String {
+  let mut msg = String::from("the observable doesn't match the marbles\n");
+  writeln!(msg, "expected: {}", marbles).unwrap();
+  writeln!(msg, "actual:   {}", render(actual, values)).unwrap();
+  writeln!(msg).unwrap();
+  let describe = |event: Option<&(usize, Marble<T, E>)>| {
+    event.map_or(String::new(), |(f, m)| format!("{:?} at {}", m, f))
+  };
+  writeln!(msg, "  {:<30} actual", "expected").unwrap();
+  for i in 0..expected.len().max(actual.len()) {
+    let (e, a) = (expected.get(i), actual.get(i));
+    let mark = if e == a { ' ' } else { 'x' };
+    writeln!(msg, "{} {:<30} {}", mark, describe(e), describe(a)).unwrap();
+  }
+  msg
+}
+
+/// Draw a timeline as marbles, items without a character in `values` are
+/// drawn as `?`.
+fn render<T: PartialEq, E>(
+  events: &Timeline<T, E>, values: &[(char, T)],
+) -> String {
+  let symbol = |marble: &Marble<T, E>| match marble {
+    Marble::Next(v) => values
+      .iter()
+      .find(|(_, value)| value == v)
+      .map_or('?', |(c, _)| *c),
+    Marble::Err(_) => '#',
+    Marble::Complete => '|',
+  };
+  let mut drawn = String::new();
+  let mut frame = 0;
+  let mut i = 0;
+  while i < events.len() {
+    let at = events[i].0;
+    while frame < at {
+      drawn.push('-');
+      frame += 1;
+    }
+    let len = events[i..].iter().take_while(|(f, _)| *f == at).count();
+    let group = events[i..i + len].iter().map(|(_, m)| symbol(m));
+    if len > 1 {
+      drawn.push('(');
+      drawn.extend(group);
+      drawn.push(')');
+      frame += len + 2;
+    } else {
+      drawn.extend(group);
+      frame += 1;
+    }
+    i += len;
+  }
+  drawn
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::timer,
+    ops::{Filter, Map, Merge},
+    scheduler::TestScheduler,
+    testing::{cold, expect_observable, hot, FRAME},
+  };
+
+  #[test]
+  fn cold_restarts_per_subscription() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2)];
+    let source = cold::<_, ()>(&scheduler, "-a-b|", &values);
+    expect_observable(&scheduler, source.clone()).to_be("-a-b|", &values);
+    // the clock is at frame 4 now.
+    expect_observable(&scheduler, source).to_be("-a-b|", &values);
+  }
+
+  #[test]
+  fn hot_skips_what_was_emitted_before() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3)];
+    let source = hot::<_, ()>(&scheduler, "a^-b--c|", &values);
+    scheduler.advance_by(FRAME * 2);
+    expect_observable(&scheduler, source).to_be("---c|", &values);
+  }
+
+  #[test]
+  fn groups_and_errors() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2), ('c', 3)];
+    let source = cold(&scheduler, "(ab)c#", &values).with_error("boom");
+    expect_observable(&scheduler, source.filter(|v| *v != 2))
+      .with_error("boom")
+      .to_be("a---c#", &values);
+  }
+
+  #[test]
+  fn odd_even_merge() {
+    let scheduler = TestScheduler::new();
+    let values = [('1', 1), ('2', 2), ('3', 3), ('4', 4), ('5', 5)];
+    let numbers = hot::<_, ()>(&scheduler, "-1-2-3-4-5|", &values);
+    let odd = numbers.clone().filter(|v| v % 2 == 1);
+    let even = numbers.filter(|v| v % 2 == 0);
+    expect_observable(&scheduler, odd.merge(even))
+      .to_be("-1-2-3-4-5|", &values);
+  }
+
+  #[test]
+  fn with_time_based_sources() {
+    let scheduler = TestScheduler::new();
+    let source = timer::<_, ()>(FRAME * 3, scheduler.clone()).map(|v| v + 1);
+    expect_observable(&scheduler, source).to_be("---(a|)", &[('a', 1)]);
+  }
+
+  #[test]
+  #[should_panic(expected = "expected: -a-b|\nactual:   -a--(b|)")]
+  fn readable_diff() {
+    let scheduler = TestScheduler::new();
+    let values = [('a', 1), ('b', 2)];
+    let source = cold::<_, ()>(&scheduler, "-a--(b|)", &values);
+    expect_observable(&scheduler, source).to_be("-a-b|", &values);
+  }
+}
@@ -0,0 +1,314 @@
+use crate::{scheduler::TestScheduler, ErrComplete, Observable, Observer};
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Records every notification it receives, for assertions.
+///
+/// Created by `with_scheduler`, each notification is timestamped with the
+/// virtual time of the `TestScheduler`, otherwise timestamps are zero.
+///
+/// Clones share the same records, so a clone can be subscribed while the
+/// test keeps another to assert on.
+///
+/// Every notification is recorded, even those breaking the grammar
+/// `next* (complete | err)?`, so the assertions also fail on a second
+/// terminal notification or a value received after the terminal one.
+///
+/// # Example
+///
+/// ```
+/// # use rx_rs::{ops::Map, testing::TestObserver, Observer, Subject};
+/// let subject = Subject::new();
+/// let observer = TestObserver::new();
+/// observer.subscribe_to(subject.clone().map(|v| v * 2));
+///
+/// subject.next(1).next(2);
+/// observer.assert_values(&[2, 4]);
+/// observer.assert_no_terminal();
+/// subject.err("boom");
+/// observer.assert_error(&"boom");
+/// ```
+pub struct TestObserver<T, E> {
+  clock: Option<Rc<Cell<Duration>>>,
+  records: Rc<RefCell<Records<T, E>>>,
+}
+
+struct Records<T, E> {
+  values: Vec<(Duration, T)>,
+  terminals: Vec<(Duration, ErrComplete<E>)>,
+  values_after_terminal: usize,
+}
+
+impl<T, E> Clone for TestObserver<T, E> {
+  fn clone(&self) -> Self {
+    TestObserver {
+      clock: self.clock.clone(),
+      records: self.records.clone(),
+    }
+  }
+}
+
+impl<T, E> Default for TestObserver<T, E> {
+  fn default() -> Self { Self::new() }
+}
+
+impl<T, E> TestObserver<T, E> {
+  pub fn new() -> Self {
+    TestObserver {
+      clock: None,
+      records: Rc::new(RefCell::new(Records {
+        values: vec![],
+        terminals: vec![],
+        values_after_terminal: 0,
+      })),
+    }
+  }
+
+  /// A recorder timestamping notifications with the virtual time of
+  /// `scheduler`.
+  pub fn with_scheduler(scheduler: &TestScheduler) -> Self {
+    TestObserver {
+      clock: Some(scheduler.clock()),
+      ..Self::new()
+    }
+  }
+
+  /// Subscribe a clone of this recorder to `observable`.
+  ///
+  /// Unlike `subscribe_observer`, which drops the observer on the terminal
+  /// notification, it keeps recording whatever the observable emits next.
+  pub fn subscribe_to<'a, O>(&self, observable: O) -> O::Unsubscribe
+  where
+    O: Observable<'a, Item = T, Err = E>,
+    T: 'a,
+    E: Clone + 'a,
+  {
+    let on_next = self.clone();
+    let on_ec = self.clone();
+    observable.subscribe(
+      move |v| on_next.push_value(v),
+      move |ec| on_ec.push_terminal(ec.clone()),
+    )
+  }
+
+  fn now(&self) -> Duration {
+    self.clock.as_ref().map_or(Duration::default(), |c| c.get())
+  }
+
+  fn push_value(&self, v: T) {
+    let at = self.now();
+    let mut records = self.records.borrow_mut();
+    if !records.terminals.is_empty() {
+      records.values_after_terminal += 1;
+    }
+    records.values.push((at, v));
+  }
+
+  fn push_terminal(&self, ec: ErrComplete<E>) {
+    let at = self.now();
+    self.records.borrow_mut().terminals.push((at, ec));
+  }
+
+  pub fn values(&self) -> Vec<T>
+  where
+    T: Clone,
+  {
+    let records = self.records.borrow();
+    records.values.iter().map(|(_, v)| v.clone()).collect()
+  }
+
+  /// The values with the virtual time they were received at.
+  pub fn timed_values(&self) -> Vec<(Duration, T)>
+  where
+    T: Clone,
+  {
+    self.records.borrow().values.clone()
+  }
+
+  /// The completion or the error received, with the virtual time it was
+  /// received at.
+  pub fn terminal(&self) -> Option<(Duration, ErrComplete<E>)>
+  where
+    E: Clone,
+  {
+    self.records.borrow().terminals.first().cloned()
+  }
+
+  pub fn assert_values(&self, expected: &[T])
+  where
+    T: Clone + PartialEq + Debug,
+  {
+    assert_eq!(self.values(), expected, "values differ");
+  }
+
+  pub fn assert_timed_values(&self, expected: &[(Duration, T)])
+  where
+    T: Clone + PartialEq + Debug,
+  {
+    assert_eq!(self.timed_values(), expected, "timed values differ");
+  }
+
+  /// Assert the observable completed, exactly once.
+  pub fn assert_completed(&self)
+  where
+    E: Debug,
+  {
+    self
+      .assert_terminal(|ec| matches!(ec, ErrComplete::Complete), "a completion")
+  }
+
+  /// Assert the observable failed with `err`, exactly once.
+  pub fn assert_error(&self, err: &E)
+  where
+    E: PartialEq + Debug,
+  {
+    self.assert_terminal(
+      |ec| matches!(ec, ErrComplete::Err(e) if e == err),
+      &format!("the error {:?}", err),
+    )
+  }
+
+  /// Assert the observable neither completed nor failed.
+  pub fn assert_no_terminal(&self)
+  where
+    E: Debug,
+  {
+    let records = self.records.borrow();
+    assert!(
+      records.terminals.is_empty(),
+      "expected no completion nor error, got {:?}",
+      records.terminals
+    );
+  }
+
+  fn assert_terminal<F>(&self, is_expected: F, expected: &str)
+  where
+    F: FnOnce(&ErrComplete<E>) -> bool,
+    E: Debug,
+  {
+    let records = self.records.borrow();
+    match records.terminals.as_slice() {
+      [(_, ec)] if is_expected(ec) => {}
+      terminals => panic!("expected {}, got {:?}", expected, terminals),
+    }
+    assert_eq!(
+      records.values_after_terminal, 0,
+      "values received after the terminal notification"
+    );
+  }
+}
+
+impl<T, E> Observer for TestObserver<T, E> {
+  type Item = T;
+  type Err = E;
+
+  fn next(&self, v: T) -> &Self {
+    self.push_value(v);
+    self
+  }
+
+  fn complete(self) { self.push_terminal(ErrComplete::Complete) }
+
+  fn err(self, err: E) { self.push_terminal(ErrComplete::Err(err)) }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    creation::{from_iter, interval},
+    scheduler::TestScheduler,
+    testing::TestObserver,
+    ErrComplete, OState, Observable, Subscription,
+  };
+  use std::time::Duration;
+
+  /// Emits `1`, completes, then breaks the grammar by emitting `2`, and
+  /// completing again if set.
+  struct Misbehaving(bool);
+
+  impl<'a> Observable<'a> for Misbehaving {
+    type Item = i32;
+    type Err = ();
+    type Unsubscribe = ();
+
+    fn subscribe_return_state<N, EC>(self, mut next: N, mut err_or_complete: EC)
+    where
+      N: 'a + FnMut(i32) -> OState,
+      EC: 'a + FnMut(&ErrComplete<()>),
+    {
+      next(1);
+      err_or_complete(&ErrComplete::Complete);
+      next(2);
+      if self.0 {
+        err_or_complete(&ErrComplete::Complete);
+      }
+    }
+  }
+
+  #[test]
+  fn records_sync_source() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(1..4));
+    observer.assert_values(&[1, 2, 3]);
+    observer.assert_completed();
+    assert_eq!(
+      observer.terminal(),
+      Some((Duration::default(), ErrComplete::Complete))
+    );
+  }
+
+  #[test]
+  fn timestamps_in_virtual_time() {
+    let secs = Duration::from_secs;
+    let scheduler = TestScheduler::new();
+    let observer = TestObserver::<_, ()>::with_scheduler(&scheduler);
+    let subscription =
+      observer.subscribe_to(interval(secs(1), scheduler.clone()));
+    scheduler.advance_by(secs(2));
+    subscription.unsubscribe();
+    scheduler.advance_by(secs(2));
+    observer.assert_timed_values(&[(secs(1), 0), (secs(2), 1)]);
+    observer.assert_no_terminal();
+  }
+
+  #[test]
+  #[should_panic(expected = "expected the error \"boom\", got []")]
+  fn error_expected() {
+    let observer = TestObserver::<i32, &str>::new();
+    observer.assert_error(&"boom");
+  }
+
+  #[test]
+  #[should_panic(expected = "expected no completion nor error, got")]
+  fn terminal_not_expected() {
+    let observer = TestObserver::<_, ()>::new();
+    observer.subscribe_to(from_iter(vec![1]));
+    observer.assert_no_terminal();
+  }
+
+  #[test]
+  fn records_notifications_after_terminal() {
+    let observer = TestObserver::new();
+    observer.subscribe_to(Misbehaving(true));
+    observer.assert_values(&[1, 2]);
+    assert_eq!(observer.records.borrow().terminals.len(), 2);
+  }
+
+  #[test]
+  #[should_panic(expected = "expected a completion, got [")]
+  fn second_terminal_fails() {
+    let observer = TestObserver::new();
+    observer.subscribe_to(Misbehaving(true));
+    observer.assert_completed();
+  }
+
+  #[test]
+  #[should_panic(expected = "values received after the terminal")]
+  fn value_after_terminal_fails() {
+    let observer = TestObserver::new();
+    observer.subscribe_to(Misbehaving(false));
+    observer.assert_completed();
+  }
+}